@@ -0,0 +1,55 @@
+// right-to-left text. terminals which reorder bidirectional text guess each line's direction
+// from its first strong character and reorder across the escape sequences for colours and
+// links, so right-to-left text is wrapped in isolates
+// (https://www.unicode.org/reports/tr9/#Explicit_Directional_Isolates). everything is still
+// printed in logical order, the blank included, and the terminal lays it out
+
+use minicloze_lib::langs::{Direction, Language};
+
+// right-to-left isolate, first strong isolate and pop directional isolate
+const RLI: char = '\u{2067}';
+const FSI: char = '\u{2068}';
+const PDI: char = '\u{2069}';
+
+// left-to-right mark, an invisible strong left-to-right character
+const LRM: char = '\u{200E}';
+
+// unknown codes are assumed to be left to right
+pub fn direction(language: &str) -> Direction {
+    Language::from_code(language).map_or(Direction::Ltr, |language| language.direction)
+}
+
+// opens an isolate for a run of text in the direction, e.g. a whole sentence printed word by word
+pub fn open(direction: Direction) -> String {
+    match direction {
+        Direction::Rtl => RLI.to_string(),
+        Direction::Ltr => String::new(),
+    }
+}
+
+pub fn close(direction: Direction) -> String {
+    match direction {
+        Direction::Rtl => PDI.to_string(),
+        Direction::Ltr => String::new(),
+    }
+}
+
+pub fn isolate(text: &str, direction: Direction) -> String {
+    format!("{}{text}{}", open(direction), close(direction))
+}
+
+// words whose direction isn't known, e.g. a definition's headword, so that punctuation after
+// them stays put
+pub fn isolate_auto(text: &str) -> String {
+    format!("{FSI}{text}{PDI}")
+}
+
+// the marker before the answer. with a right-to-left answer the whole line would be taken as
+// right to left and "> " would end up on the right, the mark keeps it on the left with the
+// answer following it
+pub fn input_marker(direction: Direction) -> String {
+    match direction {
+        Direction::Rtl => format!("> {LRM}"),
+        Direction::Ltr => String::from("> "),
+    }
+}
@@ -0,0 +1,333 @@
+// the command-line interface. running minicloze with just a language (e.g. `minicloze french`)
+// is the same as `minicloze play french`
+
+use std::path::PathBuf;
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use minicloze_lib::{
+    grading::{Grade, Mistake},
+    sentence::Strategy,
+    session,
+    stats::parse_day,
+};
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "A cloze-based language-learning game using sentences from Tatoeba"
+)]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub play: PlayArgs,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Play a round of cloze sentences (the default)
+    Play(PlayArgs),
+    /// Play the sentences you've got wrong before again, with the same blanks
+    Review(ReviewArgs),
+    /// Show how you're doing per language
+    Stats {
+        /// Only show this language
+        language: Option<String>,
+    },
+    /// List the languages available
+    Langs {
+        /// Only show languages whose name or code contains this
+        filter: Option<String>,
+    },
+    /// Import lexicons, dictionaries, sentence pairs, cached sentences or words you know
+    Import {
+        #[arg(value_enum)]
+        kind: ImportKind,
+        /// The language the files are for
+        language: String,
+        /// The files to import
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// The native language of imported cached sentences [default: English]
+        #[arg(long)]
+        native: Option<String>,
+    },
+    /// Export your progress, sentence pairs or cached sentences
+    Export {
+        #[arg(value_enum)]
+        kind: ExportKind,
+        language: String,
+        /// Write to this file instead of standard output
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// The native language of the cached sentences [default: English]
+        #[arg(long)]
+        native: Option<String>,
+    },
+    /// See what's due for review and which words keep slipping away
+    Srs {
+        #[command(subcommand)]
+        action: SrsAction,
+    },
+    /// Copy your answer history to a file
+    Backup { file: PathBuf },
+    /// Replace your answer history with a backup
+    Restore { file: PathBuf },
+    /// Manage the sentences kept for offline play
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Change your settings, kept in config.toml
+    Config {
+        /// Print the settings and where minicloze keeps its files instead
+        #[arg(long)]
+        show: bool,
+    },
+    /// Play over newline-delimited JSON, for editor plugins and bots
+    Serve {
+        /// Read requests from standard input and write responses to standard output
+        #[arg(long, required = true)]
+        stdio: bool,
+    },
+}
+
+// options left out fall back to the config file
+#[derive(Args, Clone)]
+pub struct PlayArgs {
+    /// The language to study, asked for if left out and not set in the config
+    pub language: Option<String>,
+
+    // `minicloze french inverse` from before there were flags
+    #[arg(hide = true, value_parser = ["inverse"])]
+    pub legacy_inverse: Option<String>,
+
+    /// How many sentences a round has [default: 10]
+    #[arg(short = 'n', long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub round_size: Option<usize>,
+
+    /// Fill in the target language, or the native language (inverse) [default: normal]
+    #[arg(short, long, value_enum)]
+    pub direction: Option<Direction>,
+
+    /// How the word to blank is chosen [default: random]
+    #[arg(short, long, value_enum)]
+    pub strategy: Option<StrategyArg>,
+
+    /// Where sentences come from
+    #[arg(long, value_enum, default_value_t = Source::Tatoeba)]
+    pub source: Source,
+
+    /// Seed for choosing blanks and cached sentences, to replay the same round
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// The language translations are shown in [default: English]
+    #[arg(long)]
+    pub native: Option<String>,
+
+    /// Screen-reader friendly output, without colours, links or menus
+    #[arg(long)]
+    pub plain: bool,
+
+    /// Play full screen, with a dictionary panel and the round's answers
+    #[arg(long, conflicts_with = "plain")]
+    pub tui: bool,
+}
+
+#[derive(Args)]
+pub struct ReviewArgs {
+    /// The language to review
+    pub language: String,
+
+    /// How many sentences to review at most [default: 10]
+    #[arg(short = 'n', long, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    pub round_size: Option<usize>,
+
+    /// The native language of the sentences to review [default: English]
+    #[arg(long)]
+    pub native: Option<String>,
+
+    /// Only sentences missed on or after this date, e.g. 2024-01-31
+    #[arg(long, value_parser = parse_day)]
+    pub since: Option<i64>,
+
+    /// Only sentences missed on or before this date
+    #[arg(long, value_parser = parse_day)]
+    pub until: Option<i64>,
+
+    /// Only sentences answered wrongly, or nearly right
+    #[arg(long, value_enum)]
+    pub grade: Option<GradeArg>,
+
+    /// Only sentences with this kind of mistake
+    #[arg(long, value_enum)]
+    pub mistake: Option<MistakeArg>,
+
+    /// Screen-reader friendly output, without colours, links or menus
+    #[arg(long)]
+    pub plain: bool,
+
+    /// Review full screen, with a dictionary panel and the round's answers
+    #[arg(long, conflicts_with = "plain")]
+    pub tui: bool,
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Download sentences for offline play
+    Fetch {
+        language: String,
+        /// How many sentences to download
+        #[arg(short = 'n', long, default_value_t = 100)]
+        count: usize,
+        /// The language translations are shown in [default: English]
+        #[arg(long)]
+        native: Option<String>,
+    },
+    /// List the cached languages
+    List,
+    /// Delete cached sentences for a language, or every language
+    Clear { language: Option<String> },
+}
+
+#[derive(Subcommand)]
+pub enum SrsAction {
+    /// How many cards there are and are due, and the leeches
+    Status {
+        language: String,
+        /// The language translations are shown in [default: English]
+        #[arg(long)]
+        native: Option<String>,
+    },
+    /// Fit FSRS to your answers in a language, so reviews come when you're about to forget
+    Optimize {
+        language: String,
+        /// Steps of gradient descent
+        #[arg(long, default_value_t = 200)]
+        iterations: usize,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Normal,
+    Inverse,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum StrategyArg {
+    Random,
+    Longest,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Fetch fresh sentences from tatoeba.org
+    Tatoeba,
+    /// Use sentences cached by earlier games or `minicloze cache fetch`
+    Cache,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum GradeArg {
+    Wrong,
+    Close,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum MistakeArg {
+    /// Only capitalisation differed
+    Case,
+    /// A diacritic was missing, added or different
+    Accent,
+    /// Two neighbouring letters were swapped
+    Transposition,
+    /// Another form of the same word
+    Inflection,
+    /// The stem was right but the ending wasn't
+    Ending,
+    /// A different word altogether
+    Different,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ImportKind {
+    /// A UniMorph lexicon (lemma, form, features)
+    Unimorph,
+    /// A wiktextract JSONL extract
+    Wiktextract,
+    /// StarDict (.ifo, .idx, .dict.dz) or dictd (.index, .dict.dz) files
+    Dictionary,
+    /// Tab-separated sentence pairs, used for the alignment hints
+    Corpus,
+    /// Sentences exported with `minicloze export cache`
+    Cache,
+    /// Words you already know, one per line, or Anki notes exported as plain text
+    Vocabulary,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ExportKind {
+    /// Answers tallied per word and lemma, as JSON
+    Progress,
+    /// Sentence pairs, as tab-separated text
+    Corpus,
+    /// Cached sentences, as JSON
+    Cache,
+}
+
+impl From<StrategyArg> for Strategy {
+    fn from(strategy: StrategyArg) -> Strategy {
+        match strategy {
+            StrategyArg::Random => Strategy::Random,
+            StrategyArg::Longest => Strategy::Longest,
+        }
+    }
+}
+
+impl From<GradeArg> for Grade {
+    fn from(grade: GradeArg) -> Grade {
+        match grade {
+            GradeArg::Wrong => Grade::Wrong,
+            GradeArg::Close => Grade::Close,
+        }
+    }
+}
+
+impl From<MistakeArg> for Mistake {
+    fn from(mistake: MistakeArg) -> Mistake {
+        match mistake {
+            MistakeArg::Case => Mistake::CaseOnly,
+            MistakeArg::Accent => Mistake::AccentMissing,
+            MistakeArg::Transposition => Mistake::Transposition,
+            MistakeArg::Inflection => Mistake::WrongInflection,
+            MistakeArg::Ending => Mistake::WrongEnding,
+            MistakeArg::Different => Mistake::DifferentWord,
+        }
+    }
+}
+
+impl From<Source> for session::Source {
+    fn from(source: Source) -> session::Source {
+        match source {
+            Source::Tatoeba => session::Source::Tatoeba,
+            Source::Cache => session::Source::Cache,
+        }
+    }
+}
+
+impl PlayArgs {
+    // None if the direction wasn't given
+    pub fn inverse(&self) -> Option<bool> {
+        if self.legacy_inverse.is_some() {
+            return Some(true);
+        }
+        self.direction
+            .map(|direction| direction == Direction::Inverse)
+    }
+}
@@ -0,0 +1,566 @@
+// the subcommands which aren't the game itself
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use minicloze_lib::{
+    alignment::import_pairs,
+    cache,
+    config::Config,
+    definitions::Definitions,
+    history::{self, Record},
+    known::KnownWords,
+    langs::Language,
+    localdict::LocalDictionary,
+    morph::{Lexicon, WordProgress},
+    optimize::optimize,
+    paths::{config_file, data_dir, data_file},
+    resolve,
+    sentence::{convert_error, fetch_sentences, Sentence},
+    srs::{self, Deck, Fsrs},
+    stats,
+};
+
+use crate::cli::{CacheAction, ExportKind, ImportKind, SrsAction};
+use crate::i18n::tr;
+use crate::theme::Theme;
+
+// how many of the most missed words stats shows
+const MOST_MISSED: usize = 5;
+// how far back stats' accuracy sparklines and activity heatmap go
+const ACCURACY_WEEKS: usize = 12;
+const HEATMAP_WEEKS: usize = 26;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+// a day's answers, as a share of the busiest day's
+const SHADES: [char; 4] = ['░', '▒', '▓', '█'];
+
+// accepts a tatoeba or ISO code, or a name in any case, e.g. "fra", "fr" or "french"
+pub fn resolve_language(input: &str) -> Result<String, String> {
+    resolve::resolve_language(input)
+        .map(|language| language.code.to_string())
+        .map_err(|e| format!("{e}\n{}", tr!("langs-hint")))
+}
+
+// the native language given on the command line, or else the one configured for the language
+fn native_for(language: &str, native: Option<&str>) -> Result<String, String> {
+    match native {
+        Some(native) => resolve_language(native),
+        None => resolve_language(&Config::load()?.settings(language).native),
+    }
+}
+
+fn language_name(code: &str) -> String {
+    Language::from_code(code).map_or_else(|| code.to_string(), |language| language.name.to_string())
+}
+
+pub fn langs(filter: Option<&str>) {
+    let filter = filter.map(str::to_lowercase);
+
+    // most sentences first
+    for language in Language::all() {
+        let matches = filter.as_ref().is_none_or(|f| {
+            language.display_name().to_lowercase().contains(f) || language.code.contains(f)
+        });
+
+        if matches {
+            println!("{}\t{}", language.code, language.display_name());
+        }
+    }
+}
+
+pub fn import(
+    kind: ImportKind,
+    language: &str,
+    files: &[PathBuf],
+    native: Option<&str>,
+) -> Result<(), String> {
+    let language = resolve_language(language)?;
+
+    // these formats are a single file, later ones would just replace earlier ones
+    let single = || match files {
+        [file] => Ok(file),
+        _ => Err(tr!("one-file")),
+    };
+
+    match kind {
+        ImportKind::Unimorph => {
+            let forms = Lexicon::import(&language, single()?)?;
+            println!("{}", tr!("imported-forms", count = forms));
+        }
+        ImportKind::Wiktextract => {
+            let words = Definitions::import(single()?, &language)?;
+            println!("{}", tr!("imported-definitions", count = words));
+        }
+        ImportKind::Dictionary => {
+            let dir = data_file("dictionaries", &language)?;
+            fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+            for file in files {
+                let path = file.display().to_string();
+                let name = file
+                    .file_name()
+                    .ok_or_else(|| tr!("not-a-file", path = path.clone()))?;
+                fs::copy(file, dir.join(name))
+                    .map_err(|e| tr!("copy-failed", path = path, error = e.to_string()))?;
+            }
+
+            let dictionaries = LocalDictionary::load_all(&language);
+            println!(
+                "{}",
+                tr!(
+                    "imported-dictionaries",
+                    count = dictionaries.len(),
+                    language = language_name(&language)
+                )
+            );
+        }
+        ImportKind::Corpus => {
+            let mut pairs = 0;
+            for file in files {
+                pairs += import_pairs(&language, file)?;
+            }
+            println!("{}", tr!("imported-pairs", count = pairs));
+        }
+        ImportKind::Cache => {
+            let native = native_for(&language, native)?;
+            let mut added = 0;
+
+            for file in files {
+                let json = fs::read_to_string(file).map_err(|e| {
+                    tr!(
+                        "read-failed",
+                        path = file.display().to_string(),
+                        error = e.to_string()
+                    )
+                })?;
+                let sentences: Vec<Sentence> =
+                    serde_json::from_str(&json).map_err(convert_error)?;
+
+                added += cache::store(&language, &native, &sentences)?;
+            }
+            println!("{}", tr!("imported-cache", count = added));
+        }
+        ImportKind::Vocabulary => {
+            let mut added = 0;
+            for file in files {
+                added += KnownWords::import(&language, file)?;
+            }
+            println!(
+                "{}",
+                tr!(
+                    "imported-vocabulary",
+                    count = added,
+                    language = language_name(&language)
+                )
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub fn export(
+    kind: ExportKind,
+    language: &str,
+    output: Option<&Path>,
+    native: Option<&str>,
+) -> Result<(), String> {
+    let language = resolve_language(language)?;
+
+    let text = match kind {
+        ExportKind::Progress => {
+            let progress = WordProgress::load(&language)?;
+            serde_json::to_string_pretty(&progress).map_err(convert_error)?
+        }
+        ExportKind::Corpus => {
+            fs::read_to_string(data_file("corpus", &format!("{language}.tsv"))?).unwrap_or_default()
+        }
+        ExportKind::Cache => {
+            let sentences = cache::load(&language, &native_for(&language, native)?)?;
+            serde_json::to_string_pretty(&sentences).map_err(convert_error)?
+        }
+    };
+
+    match output {
+        Some(path) => fs::write(path, text).map_err(|e| e.to_string()),
+        None => {
+            println!("{text}");
+            Ok(())
+        }
+    }
+}
+
+pub fn backup(file: &Path) -> Result<(), String> {
+    let count = history::backup(file)?;
+    let path = file.display().to_string();
+    println!("{}", tr!("backed-up", count = count, path = path));
+    Ok(())
+}
+
+pub fn restore(file: &Path) -> Result<(), String> {
+    let count = history::restore(file)?;
+    println!("{}", tr!("restored", count = count));
+    Ok(())
+}
+
+pub async fn cache(action: CacheAction) -> Result<(), String> {
+    match action {
+        CacheAction::Fetch {
+            language,
+            count,
+            native,
+        } => {
+            let language = resolve_language(&language)?;
+            let native = native_for(&language, native.as_deref())?;
+
+            let sentences = fetch_sentences(&language, &native, count).await?;
+            let added = cache::store(&language, &native, &sentences)?;
+
+            println!(
+                "{}",
+                tr!("cache-fetched", count = sentences.len(), new = added)
+            );
+        }
+        CacheAction::List => {
+            for (language, native, count) in cache::list()? {
+                println!(
+                    "{}\t{}\t{}",
+                    language_name(&language),
+                    tr!("cache-from", native = language_name(&native)),
+                    tr!("cache-sentences", count = count)
+                );
+            }
+        }
+        CacheAction::Clear { language } => {
+            let language = language.as_deref().map(resolve_language).transpose()?;
+            let removed = cache::clear(language.as_deref())?;
+            println!("{}", tr!("cache-cleared", count = removed));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn srs(action: SrsAction) -> Result<(), String> {
+    match action {
+        SrsAction::Status { language, native } => {
+            let language = resolve_language(&language)?;
+            let native = native_for(&language, native.as_deref())?;
+            let deck = Deck::load(&language, &native)?;
+            let now = Record::now();
+
+            println!(
+                "{}",
+                tr!(
+                    "srs-status",
+                    language = language_name(&language),
+                    cards = deck.cards.len(),
+                    due = deck.due(now).len()
+                )
+            );
+
+            let leeches = deck.leeches();
+            if !leeches.is_empty() {
+                println!("\n{}", tr!("srs-leeches", lapses = srs::LEECH_LAPSES));
+            }
+            for card in leeches {
+                println!(
+                    "  {}\t{}",
+                    card.word,
+                    tr!("srs-leech", sentence = card.sentence, lapses = card.lapses)
+                );
+            }
+        }
+        SrsAction::Optimize {
+            language,
+            iterations,
+        } => {
+            let language = resolve_language(&language)?;
+            let records = history::load_language(&language)?;
+            let current = Fsrs::load(&language)?;
+
+            let optimized = optimize(&records, &current, iterations)?;
+            let (before, after) = (optimized.before, optimized.after);
+
+            // numbers go in already formatted, fluent would format them its own way
+            println!(
+                "{}",
+                tr!(
+                    "srs-fitted",
+                    answers = after.reviews,
+                    cards = optimized.cards
+                )
+            );
+            println!(
+                "{}",
+                tr!(
+                    "srs-log-loss",
+                    before = format!("{:.4}", before.log_loss),
+                    after = format!("{:.4}", after.log_loss),
+                    better = format!("{:.1}", (1.0 - after.log_loss / before.log_loss) * 100.0)
+                )
+            );
+            println!(
+                "{}",
+                tr!(
+                    "srs-retention",
+                    before = format!("{:.1}", before.predicted * 100.0),
+                    after = format!("{:.1}", after.predicted * 100.0),
+                    actual = format!("{:.1}", after.actual * 100.0)
+                )
+            );
+
+            if optimized.fsrs == current {
+                println!("{}", tr!("srs-unchanged"));
+            } else {
+                optimized.fsrs.save(&language)?;
+                println!("{}", tr!("srs-saved", language = language_name(&language)));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn stats(language: Option<&str>) -> Result<(), String> {
+    let config = Config::load()?;
+    let theme = Theme::load(&config)?;
+    let mut records = history::load()?;
+
+    let languages = match language {
+        Some(language) => {
+            let language = resolve_language(language)?;
+            records.retain(|record| record.language == language);
+            vec![language]
+        }
+        None => {
+            // languages played before the history was kept only have their progress
+            let dir = data_file("lemmas", "")?;
+            let mut languages: Vec<String> = fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .filter_map(|file| {
+                    let name = file.ok()?.file_name().to_string_lossy().to_string();
+                    name.strip_suffix(".json").map(str::to_string)
+                })
+                .chain(records.iter().map(|record| record.language.clone()))
+                .collect();
+            languages.sort();
+            languages.dedup();
+            languages
+        }
+    };
+
+    let today = stats::day(Record::now());
+    let mut played = false;
+
+    for language in languages {
+        let progress = WordProgress::load(&language)?;
+        let answers: Vec<Record> = records
+            .iter()
+            .filter(|record| record.language == language)
+            .cloned()
+            .collect();
+
+        let (correct, total) = progress
+            .forms
+            .values()
+            .fold((0, 0), |(c, t), score| (c + score.correct, t + score.total));
+
+        if total == 0 && answers.is_empty() {
+            continue;
+        }
+        played = true;
+
+        if total > 0 {
+            println!(
+                "{}",
+                tr!(
+                    "stats-score",
+                    language = theme.paint(&theme.label, &language_name(&language)),
+                    correct = correct,
+                    total = total,
+                    percent = format!("{:.0}", f64::from(correct) * 100.0 / f64::from(total)),
+                    words = progress.forms.len()
+                )
+            );
+        } else {
+            println!("{}:", theme.paint(&theme.label, &language_name(&language)));
+        }
+
+        if !answers.is_empty() {
+            let weeks = stats::weekly_accuracy(&answers, today, ACCURACY_WEEKS);
+            println!(
+                "  {}",
+                tr!(
+                    "stats-accuracy",
+                    weeks = ACCURACY_WEEKS,
+                    line = accuracy_line(&weeks, config.plain)
+                )
+            );
+
+            let words = stats::word_counts(&answers);
+            println!(
+                "  {}",
+                tr!(
+                    "stats-words",
+                    seen = words.seen,
+                    learned = words.learned,
+                    mastered = words.mastered
+                )
+            );
+
+            if let Some(ms) = stats::average_response(&answers) {
+                let seconds = format!("{:.1}", ms / 1000.0);
+                println!("  {}", tr!("stats-answer-time", seconds = seconds));
+            }
+        }
+
+        for (id, scores) in [
+            ("stats-missed-words", &progress.forms),
+            ("stats-missed-lemmas", &progress.lemmas),
+        ] {
+            let mut missed: Vec<(&String, u32)> = scores
+                .iter()
+                .map(|(word, score)| (word, score.total - score.correct))
+                .filter(|(_, misses)| *misses > 0)
+                .collect();
+            missed.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+            if !missed.is_empty() {
+                let list: Vec<String> = missed
+                    .iter()
+                    .take(MOST_MISSED)
+                    .map(|(word, misses)| format!("{word} ({misses})"))
+                    .collect();
+                println!("  {}", tr!(id, list = list.join(", ")));
+            }
+        }
+    }
+
+    if !played {
+        println!("{}", tr!("stats-nothing-played"));
+        return Ok(());
+    }
+
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let activity = stats::daily_activity(&records);
+    let streaks = stats::streaks(&activity, today);
+    println!(
+        "\n{} {}",
+        theme.paint(&theme.label, &tr!("stats-streak")),
+        tr!(
+            "stats-streak-days",
+            current = streaks.current,
+            longest = streaks.longest
+        )
+    );
+
+    if config.plain {
+        let since = today - HEATMAP_WEEKS as i64 * 7;
+        let days = activity.range(since + 1..=today).count();
+        println!(
+            "{}",
+            tr!("stats-active-days", active = days, days = HEATMAP_WEEKS * 7)
+        );
+    } else {
+        println!(
+            "{}",
+            theme.paint(&theme.label, &tr!("stats-activity", weeks = HEATMAP_WEEKS))
+        );
+        for row in heatmap(&activity, today, &theme) {
+            println!("{row}");
+        }
+    }
+
+    Ok(())
+}
+
+// a sparkline, with a gap for weeks without answers, and the first and last week's accuracy
+fn accuracy_line(weeks: &[Option<f64>], plain: bool) -> String {
+    let known: Vec<f64> = weeks.iter().flatten().copied().collect();
+    let (Some(first), Some(last)) = (known.first(), known.last()) else {
+        return tr!("stats-nothing-answered");
+    };
+
+    if plain {
+        let list: Vec<String> = weeks
+            .iter()
+            .map(|week| week.map_or_else(|| String::from("-"), |a| format!("{:.0}%", a * 100.0)))
+            .collect();
+        return list.join(" ");
+    }
+
+    let line: String = weeks
+        .iter()
+        .map(|week| match week {
+            Some(accuracy) => SPARKS[((accuracy * 8.0) as usize).min(7)],
+            None => ' ',
+        })
+        .collect();
+
+    format!("{line}  ({:.0}% -> {:.0}%)", first * 100.0, last * 100.0)
+}
+
+// a row for each day of the week, monday first, and a column for each week, like github's
+fn heatmap(activity: &BTreeMap<i64, usize>, today: i64, theme: &Theme) -> Vec<String> {
+    let start = today - stats::weekday(today) as i64 - (HEATMAP_WEEKS as i64 - 1) * 7;
+    let busiest = activity
+        .range(start..=today)
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(1);
+
+    let labels = [
+        tr!("stats-monday"),
+        String::new(),
+        tr!("stats-wednesday"),
+        String::new(),
+        tr!("stats-friday"),
+        String::new(),
+        tr!("stats-sunday"),
+    ];
+
+    labels
+        .iter()
+        .enumerate()
+        .map(|(weekday, label)| {
+            let mut row = format!("  {label:<4}");
+
+            for week in 0..HEATMAP_WEEKS as i64 {
+                let day = start + week * 7 + weekday as i64;
+                if day > today {
+                    break;
+                }
+
+                let count = activity.get(&day).copied().unwrap_or(0);
+                if count == 0 {
+                    row.push('·');
+                } else {
+                    let shade = SHADES[(count * SHADES.len()).div_ceil(busiest) - 1];
+                    row.push_str(&theme.paint(&theme.correct, &shade.to_string()));
+                }
+            }
+
+            row
+        })
+        .collect()
+}
+
+pub fn show_config() -> Result<(), String> {
+    let data = data_dir().map_or_else(|| tr!("config-no-data"), |dir| dir.display().to_string());
+    let config = Config::load()?;
+    let text = toml::to_string_pretty(&config).map_err(|e| e.to_string())?;
+
+    let path = config_file()?.display().to_string();
+    println!("{}", tr!("config-file", path = path));
+    println!("{}", tr!("config-data", path = data));
+    println!("\n{text}");
+
+    Ok(())
+}
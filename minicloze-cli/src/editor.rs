@@ -0,0 +1,543 @@
+// `minicloze config`, an interactive editor for config.toml. escape goes back without changing
+// anything
+
+use std::fmt;
+
+use inquire::validator::Validation;
+use inquire::{Confirm, CustomType, InquireError, Select, Text};
+
+use minicloze_lib::{
+    config::{Config, LanguageConfig},
+    dictionary::provider_by_name,
+    paths::config_file,
+    resolve::resolve_language,
+    sentence::Strategy,
+    srs::Scheduler,
+};
+
+use crate::i18n::tr;
+use crate::theme::BUILT_IN;
+
+// what the main menu can change
+#[derive(Clone, Copy)]
+enum Item {
+    Language,
+    Native,
+    Direction,
+    RoundSize,
+    Strategy,
+    Theme,
+    Plain,
+    Dictionary,
+    Furigana,
+    CloseDistance,
+    CaseSensitive,
+    AccentSensitive,
+    Scheduler,
+    Overrides,
+    Save,
+    Quit,
+}
+
+const ITEMS: [Item; 16] = [
+    Item::Language,
+    Item::Native,
+    Item::Direction,
+    Item::RoundSize,
+    Item::Strategy,
+    Item::Theme,
+    Item::Plain,
+    Item::Dictionary,
+    Item::Furigana,
+    Item::CloseDistance,
+    Item::CaseSensitive,
+    Item::AccentSensitive,
+    Item::Scheduler,
+    Item::Overrides,
+    Item::Save,
+    Item::Quit,
+];
+
+// what a language's overrides can change
+#[derive(Clone, Copy)]
+enum Override {
+    Native,
+    Direction,
+    RoundSize,
+    Strategy,
+    Dictionary,
+    Furigana,
+    CloseDistance,
+    CaseSensitive,
+    AccentSensitive,
+    Remove,
+    Done,
+}
+
+const OVERRIDES: [Override; 11] = [
+    Override::Native,
+    Override::Direction,
+    Override::RoundSize,
+    Override::Strategy,
+    Override::Dictionary,
+    Override::Furigana,
+    Override::CloseDistance,
+    Override::CaseSensitive,
+    Override::AccentSensitive,
+    Override::Remove,
+    Override::Done,
+];
+
+// a menu entry with the current value next to it
+struct Entry<T> {
+    item: T,
+    label: String,
+}
+
+impl<T> fmt::Display for Entry<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.label)
+    }
+}
+
+pub fn edit() -> Result<(), String> {
+    let mut config = Config::load()?;
+
+    loop {
+        let entries = ITEMS
+            .iter()
+            .map(|item| Entry {
+                item: *item,
+                label: label(&config, *item),
+            })
+            .collect();
+
+        let Some(entry) =
+            skippable(Select::new(&tr!("editor-settings"), entries).prompt_skippable())?
+        else {
+            return Ok(());
+        };
+
+        match entry.item {
+            Item::Language => {
+                if let Some(language) = ask_language(&tr!("editor-ask-language"), true)? {
+                    config.language = (!language.is_empty()).then_some(language);
+                }
+            }
+            Item::Native => {
+                if let Some(native) = ask_language(&tr!("editor-ask-native"), false)? {
+                    config.native = native;
+                }
+            }
+            Item::Direction => {
+                if let Some(inverse) = ask_direction()? {
+                    config.inverse = inverse;
+                }
+            }
+            Item::RoundSize => {
+                if let Some(size) = ask_number(&tr!("editor-ask-round-size"), config.round_size)? {
+                    config.round_size = size;
+                }
+            }
+            Item::Strategy => {
+                if let Some(strategy) = ask_strategy()? {
+                    config.strategy = strategy;
+                }
+            }
+            Item::Theme => {
+                // the built-in themes, then the ones in the config file
+                let themes: Vec<String> = BUILT_IN
+                    .iter()
+                    .map(|name| name.to_string())
+                    .chain(
+                        config
+                            .themes
+                            .keys()
+                            .filter(|name| !BUILT_IN.contains(&name.as_str()))
+                            .cloned(),
+                    )
+                    .collect();
+                let current = themes
+                    .iter()
+                    .position(|name| *name == config.theme)
+                    .unwrap_or(0);
+                let theme = Select::new(&tr!("editor-ask-theme"), themes)
+                    .with_starting_cursor(current)
+                    .prompt_skippable();
+                if let Some(theme) = skippable(theme)? {
+                    config.theme = theme;
+                }
+            }
+            Item::Plain => {
+                let message = tr!("editor-ask-plain");
+                if let Some(plain) = ask_bool(&message, config.plain)? {
+                    config.plain = plain;
+                }
+            }
+            Item::Dictionary => {
+                if let Some(dictionary) = ask_dictionary(&config.dictionary)? {
+                    config.dictionary = dictionary;
+                }
+            }
+            Item::Furigana => {
+                let message = tr!("editor-ask-furigana");
+                if let Some(furigana) = ask_bool(&message, config.furigana)? {
+                    config.furigana = furigana;
+                }
+            }
+            Item::CloseDistance => {
+                let message = tr!("editor-ask-close-distance");
+                if let Some(distance) = ask_number(&message, config.grading.close_distance)? {
+                    config.grading.close_distance = distance;
+                }
+            }
+            Item::CaseSensitive => {
+                let current = config.grading.case_sensitive;
+                if let Some(sensitive) = ask_bool(&tr!("editor-ask-case-sensitive"), current)? {
+                    config.grading.case_sensitive = sensitive;
+                }
+            }
+            Item::AccentSensitive => {
+                let current = config.grading.accent_sensitive;
+                if let Some(sensitive) = ask_bool(&tr!("editor-ask-accent-sensitive"), current)? {
+                    config.grading.accent_sensitive = sensitive;
+                }
+            }
+            Item::Scheduler => {
+                let choices = vec![Scheduler::Fsrs, Scheduler::Sm2, Scheduler::Off];
+                let labels = choices.iter().map(|s| scheduler(*s)).collect();
+                let message = tr!("editor-ask-scheduler");
+                let answer = Select::new(&message, labels).prompt_skippable();
+                if let Some(label) = skippable(answer)? {
+                    config.scheduler = choices
+                        .into_iter()
+                        .find(|s| scheduler(*s) == label)
+                        .unwrap_or_default();
+                }
+            }
+            Item::Overrides => edit_overrides(&mut config)?,
+            Item::Save => {
+                config.save()?;
+                let path = config_file()?.display().to_string();
+                println!("{}", tr!("editor-saved", path = path));
+                return Ok(());
+            }
+            Item::Quit => return Ok(()),
+        }
+    }
+}
+
+fn label(config: &Config, item: Item) -> String {
+    match item {
+        Item::Language => tr!(
+            "editor-language",
+            value = config.language.clone().unwrap_or_else(|| tr!("editor-ask"))
+        ),
+        Item::Native => tr!("editor-native", value = config.native.clone()),
+        Item::Direction => tr!("editor-direction", value = direction(config.inverse)),
+        Item::RoundSize => tr!("editor-round-size", value = config.round_size),
+        Item::Strategy => tr!("editor-strategy", value = strategy(config.strategy)),
+        Item::Theme => tr!("editor-theme", value = config.theme.clone()),
+        Item::Plain => tr!("editor-plain", value = yes_no(config.plain)),
+        Item::Dictionary => tr!("editor-dictionary", value = config.dictionary.clone()),
+        Item::Furigana => tr!("editor-furigana", value = yes_no(config.furigana)),
+        Item::CloseDistance => tr!(
+            "editor-close-distance",
+            value = config.grading.close_distance
+        ),
+        Item::CaseSensitive => tr!(
+            "editor-case-sensitive",
+            value = yes_no(config.grading.case_sensitive)
+        ),
+        Item::AccentSensitive => tr!(
+            "editor-accent-sensitive",
+            value = yes_no(config.grading.accent_sensitive)
+        ),
+        Item::Scheduler => tr!("editor-scheduler", value = scheduler(config.scheduler)),
+        Item::Overrides => tr!("editor-overrides", count = config.languages.len()),
+        Item::Save => tr!("editor-save"),
+        Item::Quit => tr!("editor-quit"),
+    }
+}
+
+fn edit_overrides(config: &mut Config) -> Result<(), String> {
+    let Some(language) = ask_language(&tr!("editor-which-language"), false)? else {
+        return Ok(());
+    };
+    let code = resolve_language(&language)?.code;
+
+    // keep the key the config already uses for the language, whatever it's called
+    let key = config
+        .languages
+        .keys()
+        .find(|key| resolve_language(key).is_ok_and(|l| l.code == code))
+        .cloned()
+        .unwrap_or(language);
+
+    let mut overrides = config.languages.get(&key).cloned().unwrap_or_default();
+
+    loop {
+        let entries = OVERRIDES
+            .iter()
+            .map(|item| Entry {
+                item: *item,
+                label: override_label(&overrides, *item),
+            })
+            .collect();
+
+        let message = tr!("editor-overrides-for", language = key.clone());
+        let Some(entry) = skippable(Select::new(&message, entries).prompt_skippable())? else {
+            break;
+        };
+
+        // each question can also go back to the default
+        match entry.item {
+            Override::Native => {
+                overrides.native =
+                    ask_override(|| ask_language(&tr!("editor-override-native"), false))?
+                        .unwrap_or(overrides.native);
+            }
+            Override::Direction => {
+                overrides.inverse = ask_override(ask_direction)?.unwrap_or(overrides.inverse);
+            }
+            Override::RoundSize => {
+                let current = overrides.round_size.unwrap_or(config.round_size);
+                overrides.round_size =
+                    ask_override(|| ask_number(&tr!("editor-ask-round-size"), current))?
+                        .unwrap_or(overrides.round_size);
+            }
+            Override::Strategy => {
+                overrides.strategy = ask_override(ask_strategy)?.unwrap_or(overrides.strategy);
+            }
+            Override::Dictionary => {
+                let current = overrides.dictionary.clone();
+                let current = current.as_deref().unwrap_or(&config.dictionary);
+                overrides.dictionary =
+                    ask_override(|| ask_dictionary(current))?.unwrap_or(overrides.dictionary);
+            }
+            Override::Furigana => {
+                let current = overrides.furigana.unwrap_or(config.furigana);
+                overrides.furigana =
+                    ask_override(|| ask_bool(&tr!("editor-ask-furigana"), current))?
+                        .unwrap_or(overrides.furigana);
+            }
+            Override::CloseDistance => {
+                let grading = &mut overrides.grading;
+                let current = grading
+                    .close_distance
+                    .unwrap_or(config.grading.close_distance);
+                grading.close_distance =
+                    ask_override(|| ask_number(&tr!("editor-ask-close-distance"), current))?
+                        .unwrap_or(grading.close_distance);
+            }
+            Override::CaseSensitive => {
+                let grading = &mut overrides.grading;
+                let current = grading
+                    .case_sensitive
+                    .unwrap_or(config.grading.case_sensitive);
+                grading.case_sensitive =
+                    ask_override(|| ask_bool(&tr!("editor-ask-case-sensitive"), current))?
+                        .unwrap_or(grading.case_sensitive);
+            }
+            Override::AccentSensitive => {
+                let grading = &mut overrides.grading;
+                let current = grading
+                    .accent_sensitive
+                    .unwrap_or(config.grading.accent_sensitive);
+                grading.accent_sensitive =
+                    ask_override(|| ask_bool(&tr!("editor-ask-accent-sensitive"), current))?
+                        .unwrap_or(grading.accent_sensitive);
+            }
+            Override::Remove => {
+                overrides = LanguageConfig::default();
+            }
+            Override::Done => break,
+        }
+    }
+
+    if overrides == LanguageConfig::default() {
+        config.languages.remove(&key);
+    } else {
+        config.languages.insert(key, overrides);
+    }
+
+    Ok(())
+}
+
+fn override_label(overrides: &LanguageConfig, item: Override) -> String {
+    fn or_default<T>(value: Option<T>, show: impl Fn(T) -> String) -> String {
+        value.map_or_else(|| tr!("editor-default"), show)
+    }
+
+    let grading = &overrides.grading;
+
+    match item {
+        Override::Native => tr!(
+            "editor-native",
+            value = or_default(overrides.native.as_ref(), String::clone)
+        ),
+        Override::Direction => tr!(
+            "editor-direction",
+            value = or_default(overrides.inverse, direction)
+        ),
+        Override::RoundSize => tr!(
+            "editor-round-size",
+            value = or_default(overrides.round_size, |n| n.to_string())
+        ),
+        Override::Strategy => tr!(
+            "editor-strategy",
+            value = or_default(overrides.strategy, strategy)
+        ),
+        Override::Dictionary => tr!(
+            "editor-dictionary",
+            value = or_default(overrides.dictionary.as_ref(), String::clone)
+        ),
+        Override::Furigana => tr!(
+            "editor-furigana",
+            value = or_default(overrides.furigana, yes_no)
+        ),
+        Override::CloseDistance => tr!(
+            "editor-close-distance",
+            value = or_default(grading.close_distance, |n| n.to_string())
+        ),
+        Override::CaseSensitive => tr!(
+            "editor-case-sensitive",
+            value = or_default(grading.case_sensitive, yes_no)
+        ),
+        Override::AccentSensitive => tr!(
+            "editor-accent-sensitive",
+            value = or_default(grading.accent_sensitive, yes_no)
+        ),
+        Override::Remove => tr!("editor-remove"),
+        Override::Done => tr!("editor-done"),
+    }
+}
+
+// Some(None) to go back to the default, Some(Some(value)) for a new value, None to keep it
+fn ask_override<T>(
+    ask: impl FnOnce() -> Result<Option<T>, String>,
+) -> Result<Option<Option<T>>, String> {
+    let change = tr!("editor-change");
+    let choices = vec![change.clone(), tr!("editor-use-default")];
+
+    match skippable(Select::new(&tr!("editor-override"), choices).prompt_skippable())? {
+        Some(choice) if choice == change => Ok(ask()?.map(Some)),
+        Some(_) => Ok(Some(None)),
+        None => Ok(None),
+    }
+}
+
+// a language's name as resolve_language understands it. an empty answer is allowed if optional
+fn ask_language(message: &str, optional: bool) -> Result<Option<String>, String> {
+    let answer = Text::new(message)
+        .with_validator(move |input: &str| {
+            if optional && input.trim().is_empty() {
+                return Ok(Validation::Valid);
+            }
+            Ok(match resolve_language(input) {
+                Ok(_) => Validation::Valid,
+                Err(e) => Validation::Invalid(e.into()),
+            })
+        })
+        .prompt_skippable();
+
+    let answer = skippable(answer)?;
+
+    Ok(answer.map(|input| match resolve_language(&input) {
+        Ok(language) => language.name.to_string(),
+        Err(_) => String::new(),
+    }))
+}
+
+fn ask_direction() -> Result<Option<bool>, String> {
+    let choices = vec![direction(false), direction(true)];
+    let answer = skippable(Select::new(&tr!("editor-ask-direction"), choices).prompt_skippable())?;
+
+    Ok(answer.map(|choice| choice == direction(true)))
+}
+
+fn ask_strategy() -> Result<Option<Strategy>, String> {
+    let choices = vec![Strategy::Random, Strategy::Longest];
+    let labels = choices.iter().map(|s| strategy(*s)).collect();
+    let answer = skippable(Select::new(&tr!("editor-ask-strategy"), labels).prompt_skippable())?;
+
+    Ok(answer.and_then(|label| choices.into_iter().find(|s| strategy(*s) == label)))
+}
+
+fn ask_dictionary(current: &str) -> Result<Option<String>, String> {
+    let message = tr!("editor-ask-dictionary");
+    let help = tr!("editor-dictionary-help");
+    let answer = Text::new(&message)
+        .with_default(current)
+        .with_help_message(&help)
+        .with_validator(|input: &str| {
+            Ok(if provider_by_name(input.trim()).is_some() {
+                Validation::Valid
+            } else {
+                Validation::Invalid(tr!("editor-unknown-dictionary").into())
+            })
+        })
+        .prompt_skippable();
+
+    Ok(skippable(answer)?.map(|dictionary| dictionary.trim().to_string()))
+}
+
+fn ask_number(message: &str, current: usize) -> Result<Option<usize>, String> {
+    let answer = CustomType::<usize>::new(message)
+        .with_default(current)
+        .with_validator(|n: &usize| {
+            Ok(if *n > 0 {
+                Validation::Valid
+            } else {
+                Validation::Invalid(tr!("editor-at-least-one").into())
+            })
+        })
+        .prompt_skippable();
+
+    skippable(answer)
+}
+
+fn ask_bool(message: &str, current: bool) -> Result<Option<bool>, String> {
+    skippable(
+        Confirm::new(message)
+            .with_default(current)
+            .prompt_skippable(),
+    )
+}
+
+// escape gives Ok(None), ctrl-c and anything else goes wrong is an error
+fn skippable<T>(answer: Result<Option<T>, InquireError>) -> Result<Option<T>, String> {
+    answer.map_err(|e| e.to_string())
+}
+
+fn direction(inverse: bool) -> String {
+    if inverse {
+        tr!("editor-inverse")
+    } else {
+        tr!("editor-normal")
+    }
+}
+
+fn strategy(strategy: Strategy) -> String {
+    match strategy {
+        Strategy::Random => tr!("editor-random"),
+        Strategy::Longest => tr!("editor-longest"),
+    }
+}
+
+// the algorithms' names aren't translated
+fn scheduler(scheduler: Scheduler) -> String {
+    match scheduler {
+        Scheduler::Fsrs => String::from("FSRS"),
+        Scheduler::Sm2 => String::from("SM-2"),
+        Scheduler::Off => tr!("editor-off"),
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    if value {
+        tr!("yes")
+    } else {
+        tr!("no")
+    }
+}
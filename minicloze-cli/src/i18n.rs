@@ -0,0 +1,17 @@
+// the interface's text, from the catalogs in minicloze-lib, see minicloze_lib::i18n
+
+pub use minicloze_lib::i18n::{init, render};
+
+// a message with arguments, e.g. tr!("correct", answer = word)
+macro_rules! tr {
+    ($id:expr) => {
+        minicloze_lib::i18n::message($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = minicloze_lib::i18n::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        minicloze_lib::i18n::message($id, Some(&args))
+    }};
+}
+
+pub(crate) use tr;
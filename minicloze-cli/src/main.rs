@@ -1,194 +1,513 @@
+mod bidi;
+mod cli;
+mod commands;
+mod editor;
+mod i18n;
+mod serve;
+mod terminal;
+mod theme;
+mod tui;
+
 use minicloze_lib::{
-    langs::propagate,
-    sentence::Sentence,
-    sentence::{generate_sentences, remove_punctuation},
-    wiktionary::generate_url,
+    alignment::{record_pairs, AlignmentModel},
+    config::Config,
+    dictionary::{
+        gloss_words, local_dictionaries, lookup_first, provider_by_name, url_or_wiktionary,
+        DictionaryProvider, Wiktionary,
+    },
+    grading::{DiffOp, Feedback, Grade, Mistake},
+    langs::{is_spaced, Direction, Language},
+    missed::MissFilter,
+    morph::Lexicon,
+    sentence::{remove_punctuation, Sentence, Strategy},
+    session::{self, GameSession, SessionOptions, Summary},
 };
 
-use levenshtein::levenshtein;
-
 use std::io;
 use std::io::Write;
+use std::process::exit;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
 use std::time::Instant;
-use std::{env, process::exit};
 
-use inline_colorization::*;
+use clap::Parser;
 use inquire::*;
-use terminal_link::*;
 
-use async_recursion::async_recursion;
+use cli::{Cli, Command, PlayArgs, ReviewArgs};
+use commands::resolve_language;
+use i18n::tr;
+use terminal::Terminal;
+use theme::Theme;
+
+// optional per-language files from the data dir, and where words link to
+struct LanguageData {
+    // UniMorph lexicon, for inflection feedback
+    lexicon: Option<Lexicon>,
+    // wiktextract, StarDict and dictd dictionaries, for definitions and :gloss
+    local: Vec<Box<dyn DictionaryProvider>>,
+    dictionary: Box<dyn DictionaryProvider>,
+}
+
+impl LanguageData {
+    // both are optional, the game works the same without them
+    fn load(language: &str, dictionary: &str) -> LanguageData {
+        LanguageData {
+            lexicon: Lexicon::load(language).unwrap_or(None),
+            local: local_dictionaries(language),
+            dictionary: pick_dictionary(dictionary),
+        }
+    }
+}
+
+// the alignment model for the hints, which is loaded on another thread because training it on
+// a big corpus takes a while. the round starts straight away and hints show once it's ready
+#[derive(Default)]
+struct Alignment {
+    model: Option<AlignmentModel>,
+    loading: Option<Receiver<Option<AlignmentModel>>>,
+}
+
+impl Alignment {
+    // adds the round's sentences to the corpus and reloads the model, which retrains it if the
+    // corpus has grown enough. the last round's model is used until then
+    fn refresh(&mut self, language: &str, sentences: Vec<Sentence>) {
+        let (sender, receiver) = mpsc::channel();
+        let language = language.to_string();
 
-const DISTANCE_FOR_CLOSE: i32 = 3;
+        thread::spawn(move || {
+            let _ = record_pairs(&language, &sentences);
+            let _ = sender.send(AlignmentModel::load(&language).ok());
+        });
+
+        self.loading = Some(receiver);
+    }
+
+    // the latest model, without waiting for one that's still loading
+    fn get(&mut self) -> Option<&AlignmentModel> {
+        if let Some(Ok(model)) = self.loading.as_ref().map(Receiver::try_recv) {
+            self.model = model.or(self.model.take());
+            self.loading = None;
+        }
+
+        self.model.as_ref()
+    }
+}
+
+// how the game is shown, the game itself is a GameSession
+struct GameOptions {
+    furigana: bool,
+    // see dictionary::provider_by_name
+    dictionary: String,
+    theme: Theme,
+    terminal: Terminal,
+    // full screen, see tui.rs
+    tui: bool,
+}
 
 #[tokio::main]
 async fn main() {
-    clear_screen();
+    let cli = Cli::parse();
+
+    // the other subcommands are shown in the configured native language, while play and review
+    // can be given another
+    if !matches!(
+        cli.command,
+        None | Some(Command::Play(_) | Command::Review(_))
+    ) {
+        let native = Config::load().map(|config| config.native);
+        if let Ok(native) = native.and_then(|native| resolve_language(&native)) {
+            i18n::init(&native);
+        }
+    }
+
+    let result = match cli.command {
+        None => play(cli.play).await,
+        Some(Command::Play(args)) => play(args).await,
+        Some(Command::Review(args)) => review(args).await,
+        Some(Command::Stats { language }) => commands::stats(language.as_deref()),
+        Some(Command::Langs { filter }) => {
+            commands::langs(filter.as_deref());
+            Ok(())
+        }
+        Some(Command::Import {
+            kind,
+            language,
+            files,
+            native,
+        }) => commands::import(kind, &language, &files, native.as_deref()),
+        Some(Command::Export {
+            kind,
+            language,
+            output,
+            native,
+        }) => commands::export(kind, &language, output.as_deref(), native.as_deref()),
+        Some(Command::Backup { file }) => commands::backup(&file),
+        Some(Command::Restore { file }) => commands::restore(&file),
+        Some(Command::Cache { action }) => commands::cache(action).await,
+        Some(Command::Srs { action }) => commands::srs(action),
+        Some(Command::Config { show: true }) => commands::show_config(),
+        Some(Command::Config { show: false }) => editor::edit(),
+        Some(Command::Serve { .. }) => serve::stdio().await,
+    };
 
-    let args: Vec<_> = env::args().collect();
+    if let Err(message) = result {
+        eprintln!("{message}");
+        exit(1);
+    }
+}
+
+async fn play(args: PlayArgs) -> Result<(), String> {
+    let mut config = Config::load()?;
+    config.plain |= args.plain;
 
-    // gets the tatoeba language codes from a separate file
-    let lang_codes = propagate();
-    let langs: Vec<&str> = lang_codes.clone().into_keys().collect();
+    let terminal = Terminal::detect(config.plain);
+    terminal.clear_screen();
 
-    let inverse = args.len() > 2 && args[2] == "inverse";
+    // the interface is in the native language, whichever language is played
+    i18n::init(&resolve_language(
+        args.native.as_ref().unwrap_or(&config.native),
+    )?);
+
+    // plain mode can be on in the config, which clap doesn't know about
+    if args.tui && config.plain {
+        return Err(tr!("tui-plain"));
+    }
 
-    let language_input = if args.len() > 1 {
-        // titlecase the input from the command line
-        args[1].to_string().remove(0).to_uppercase().to_string() + &args[1][1..]
+    let language_input = if let Some(language) = args.language.as_ref().or(config.language.as_ref())
+    {
+        language.clone()
+    }
+    // a menu of hundreds of languages is no use to a screen reader
+    else if terminal.plain {
+        print!("{} ", tr!("pick-language"));
+        let mut answer = String::new();
+        read_into(&mut answer);
+        answer.trim().to_string()
     }
     // if compiled script is run
     else {
-        let ans: Result<&str, InquireError> =
-            Select::new("What language do you want to study?", langs)
-                .without_help_message()
-                .prompt();
+        // most sentences first, with their endonyms so they can be searched for either way
+        let langs: Vec<String> = Language::all().iter().map(Language::display_name).collect();
+
+        let ans = Select::new(&tr!("pick-language"), langs)
+            .without_help_message()
+            .raw_prompt();
 
         if let Ok(choice) = ans {
-            String::from(choice)
+            String::from(Language::all()[choice.index].code)
         } else {
             String::new()
         }
     };
-    print!("Fetching sentences for you...");
+
+    let language = resolve_language(&language_input)?;
+    let settings = config.settings(&language);
+
+    let session = SessionOptions {
+        native: resolve_language(args.native.as_ref().unwrap_or(&settings.native))?,
+        inverse: args.inverse().unwrap_or(settings.inverse),
+        round_size: args.round_size.unwrap_or(settings.round_size),
+        strategy: args.strategy.map_or(settings.strategy, Strategy::from),
+        source: args.source.into(),
+        review: false,
+        filter: MissFilter::default(),
+        grading: settings.grading,
+        scheduler: settings.scheduler,
+        language,
+    };
+
+    let options = GameOptions {
+        furigana: settings.furigana,
+        dictionary: settings.dictionary,
+        theme: Theme::load(&config)?,
+        terminal,
+        tui: args.tui,
+    };
+
+    start(GameSession::new(session, args.seed), options).await
+}
+
+async fn review(args: ReviewArgs) -> Result<(), String> {
+    let mut config = Config::load()?;
+    config.plain |= args.plain;
+
+    let terminal = Terminal::detect(config.plain);
+    terminal.clear_screen();
+
+    i18n::init(&resolve_language(
+        args.native.as_ref().unwrap_or(&config.native),
+    )?);
+
+    // plain mode can be on in the config, which clap doesn't know about
+    if args.tui && config.plain {
+        return Err(tr!("tui-plain"));
+    }
+
+    let language = resolve_language(&args.language)?;
+    let settings = config.settings(&language);
+
+    let session = SessionOptions {
+        native: resolve_language(args.native.as_ref().unwrap_or(&settings.native))?,
+        inverse: false,
+        round_size: args.round_size.unwrap_or(settings.round_size),
+        strategy: Strategy::Random,
+        source: session::Source::Cache,
+        review: true,
+        filter: MissFilter {
+            since: args.since,
+            until: args.until,
+            grade: args.grade.map(Grade::from),
+            mistake: args.mistake.map(Mistake::from),
+        },
+        grading: settings.grading,
+        scheduler: settings.scheduler,
+        language,
+    };
+
+    let options = GameOptions {
+        furigana: settings.furigana,
+        dictionary: settings.dictionary,
+        theme: Theme::load(&config)?,
+        terminal,
+        tui: args.tui,
+    };
+
+    start(GameSession::new(session, None), options).await
+}
+
+async fn start(mut session: GameSession, options: GameOptions) -> Result<(), String> {
+    print!("{}", tr!("fetching"));
     io::stdout().flush().unwrap();
 
     let now = Instant::now();
 
-    let language = lang_codes
-        .get(&language_input.as_str())
-        .expect("Please enter a valid language")
-        .to_string();
-
-    let sentences = generate_sentences(&language).await.unwrap();
-    let len = sentences.len();
+    let len = load_round(&mut session).await?;
     let elapsed = now.elapsed();
 
     println!(
-        " Processing complete in {:.2?}, {} sentences parsed.",
-        elapsed, len
+        " {}",
+        tr!("fetched", time = format!("{elapsed:.2?}"), count = len)
     );
 
-    start_game(sentences, len, language, 0, 0, inverse).await;
+    let language = &session.options().language;
+
+    let data = LanguageData::load(language, &options.dictionary);
+
+    if options.tui {
+        return tui::run(&mut session, &data, &options.theme).await;
+    }
+
+    let mut alignment = Alignment::default();
+
+    loop {
+        play_round(&mut session, &options, &data, &mut alignment)?;
+
+        if !ask_replay(&session.summary(), options.terminal) {
+            return Ok(());
+        }
+
+        session.replay()?;
+        load_round(&mut session).await?;
+    }
 }
 
-// sentences: sentences for the game
-// len: how many sentences there are. always 10 if the language has enough sentences
-// language: what language the game is in
-// previous_correct: the total previous correct score
-// total: the previous total
+// the next round's sentences, with the errors in the interface's language
+async fn load_round(session: &mut GameSession) -> Result<usize, String> {
+    let review = session.options().review;
+    let fetched = session.options().source == session::Source::Tatoeba && !review;
+
+    let len = session.load_round().await.map_err(|e| {
+        if fetched {
+            tr!("fetch-failed", error = e)
+        } else {
+            e
+        }
+    })?;
+
+    match len {
+        0 if review => Err(tr!("nothing-to-review")),
+        0 => Err(tr!("no-sentences")),
+        len => Ok(len),
+    }
+}
 
-#[async_recursion]
-async fn start_game(
-    sentences: Vec<Sentence>,
-    len: usize,
-    language: String,
-    previous_correct: i32,
-    total: i32,
-    inverse: bool,
-) {
-    clear_screen();
-    let mut correct = 0;
+// plays the session's current round through
+fn play_round(
+    session: &mut GameSession,
+    options: &GameOptions,
+    data: &LanguageData,
+    alignment: &mut Alignment,
+) -> Result<(), String> {
+    let terminal = options.terminal;
+    terminal.clear_screen();
+    let language = &session.options().language.clone();
+    let native = &session.options().native.clone();
+    let inverse = session.options().inverse;
+    let theme = &options.theme;
+
+    let url = |word: &str| url_or_wiktionary(data.dictionary.as_ref(), word, language);
+
+    // the alignment corpus only has english translations
+    if native == "eng" {
+        let sentences = session.round().iter().map(|(s, _)| s.clone()).collect();
+        alignment.refresh(language, sentences);
+    }
+    let unspaced = !is_spaced(language);
+    let direction = bidi::direction(language);
+    // the blank is english in inverse mode
+    let answer_direction = if inverse { Direction::Ltr } else { direction };
 
-    for sentence in sentences {
-        let prompt = sentence.generate_prompt(&language, inverse);
+    while let Some((sentence, prompt)) = session.current().cloned() {
+        let model = alignment.get();
 
+        // the length would give the english word away
         let underscores_num = if inverse {
-            String::from("?")
+            terminal.blank(None)
         } else {
-            vec!['_'; prompt.word.chars().count()]
-                .into_iter()
-                .collect::<String>()
+            terminal.blank(Some(prompt.word.chars().count()))
         };
 
-        let print_language = if inverse { "eng" } else { &language };
+        let native_label = native.to_uppercase();
+        let print_language = if inverse {
+            native_label.as_str()
+        } else {
+            language
+        };
 
         let non_english = format!(
-            "{style_bold}{}{style_reset}{}{style_bold}{}{style_reset} {}",
-            (print_language.to_uppercase() + ": "),
+            "{}{}{}{}{}",
+            theme.paint(&theme.label, &(print_language.to_uppercase() + ": ")),
             prompt.first_half,
-            underscores_num,
+            theme.paint(&theme.label, &underscores_num),
+            prompt.separator(language),
             prompt.second_half
         );
 
         if inverse {
+            let translation = &sentence.get_translation().unwrap().text;
+
+            let underlined =
+                underline_aligned(translation, unspaced, theme, &theme.sentence, |tokens| {
+                    model.map(|model| model.align_english(&prompt.word, tokens))
+                });
+
             println!(
-                "{color_black}{bg_bright_white}{}{}{}{color_reset}{bg_reset}",
-                &language.to_uppercase(),
-                &": ".to_string(),
-                &sentence.get_translation().unwrap().text
+                "{}",
+                theme.paint(
+                    &theme.sentence,
+                    &format!(
+                        "{}: {}",
+                        language.to_uppercase(),
+                        bidi::isolate(&underlined, direction)
+                    )
+                )
             );
             println!("{}", &non_english);
         } else {
             print!(
-                "{color_black}{bg_bright_white}{style_bold}{}:{style_reset}",
-                // {color_black}{bg_bright_white}{}{style_bold}{}{style_reset}{color_black}{bg_bright_white} {}{color_reset}{bg_reset}"
-                print_language.to_uppercase()
+                "{}",
+                theme.paint(
+                    &(theme.sentence.clone() + &theme.label),
+                    &format!("{}:", print_language.to_uppercase())
+                )
             );
+            print!("{}", bidi::open(direction));
 
-            for word in prompt.first_half.split(' ') {
-                print!(
-                    "{color_black}{bg_bright_white} {}{style_reset}",
-                    Link::new(
-                        word,
-                        &generate_url(
-                            word.trim_matches(|c| char::is_ascii_punctuation(&c)),
-                            &language
-                        )
-                    )
+            let linked = |word: &str| {
+                let target = url(word.trim_matches(|c| char::is_ascii_punctuation(&c)));
+                theme.paint(
+                    &theme.sentence,
+                    &format!(" {}", terminal.link(word, &target)),
                 )
+            };
+
+            for word in prompt.first_half.split(' ') {
+                print!("{}", linked(word));
             }
 
-            print!("{color_black}{bg_bright_white}{underscores_num}{style_reset}");
+            print!("{}", theme.paint(&theme.blank, &underscores_num));
 
             for word in prompt.second_half.split(' ') {
-                print!(
-                    "{color_black}{bg_bright_white} {}{style_reset}",
-                    Link::new(
-                        word,
-                        &generate_url(
-                            word.trim_matches(|c| char::is_ascii_punctuation(&c)),
-                            &language
-                        )
-                    )
-                )
+                print!("{}", linked(word));
             }
 
-            println!("\n{style_bold}ENG:{style_reset} {}", sentence.text);
+            print!("{}", bidi::close(direction));
+
+            println!(
+                "\n{} {}",
+                theme.paint(&theme.label, &format!("{native_label}:")),
+                underline_aligned(&sentence.text, false, theme, "", |tokens| {
+                    model.map(|model| model.align_foreign(&prompt.word, tokens))
+                })
+            );
         }
 
         let mut guess = String::new();
 
-        print!("> ");
-        read_into(&mut guess);
+        // :gloss defines every visible word of the sentence, then asks again
+        loop {
+            print!("{}", bidi::input_marker(answer_direction));
+            read_into(&mut guess);
 
-        let levenshtein_distance =
-            levenshtein(&remove_punctuation(guess.trim()), prompt.word.trim());
+            if guess.trim() != ":gloss" {
+                break;
+            }
 
-        if levenshtein_distance == 0 {
-            correct += 1;
-            println!(
-                "Correct, {color_white}{bg_green}{}{color_reset}{bg_reset}",
-                Link::new(
-                    prompt.word.trim(),
-                    &generate_url(prompt.word.trim(), &language)
-                )
-            );
-        } else if levenshtein_distance < DISTANCE_FOR_CLOSE as usize {
-            println!(
-                "Close, {style_bold}{color_bright_white}{bg_yellow}{}{bg_reset}{color_reset}{style_reset}.",
-                Link::new(
-                    prompt.word.trim(),
-                    &generate_url(prompt.word.trim(), &language)
-                )
-            );
-        } else {
-            println!(
-                "Wrong, {style_bold}{color_bright_white}{bg_red}{}{bg_reset}{color_reset}{style_reset}.",
-                Link::new(
-                    prompt.word.trim(),
-                    &generate_url(prompt.word.trim(), &language)
-                )
-            );
+            if inverse {
+                print_glosses(
+                    data,
+                    theme,
+                    &sentence.get_translation().unwrap().text,
+                    language,
+                );
+            } else {
+                print_glosses(data, theme, &prompt.first_half, language);
+                print_glosses(data, theme, &prompt.second_half, language);
+            }
+            guess.clear();
+        }
+
+        let feedback = session.submit(&guess, data.lexicon.as_ref())?;
+
+        let answer_link = terminal.link(
+            &bidi::isolate(prompt.word.trim(), answer_direction),
+            &url(prompt.word.trim()),
+        );
+
+        match feedback.grade {
+            Grade::Correct => {
+                let answer = theme.paint(&theme.correct, &answer_link);
+                println!("{}", tr!("correct", answer = answer));
+                // a mistake the grading policy lets through, e.g. capitalisation
+                if let Some(mistake) = feedback.mistake {
+                    println!("  ({})", i18n::render(&mistake.describe()));
+                }
+            }
+            Grade::Close => {
+                let answer = theme.paint(&theme.close, &answer_link);
+                println!("{}", tr!("close", answer = answer));
+                print_diff(&feedback, options, answer_direction);
+            }
+            Grade::Wrong => {
+                let answer = theme.paint(&theme.wrong, &answer_link);
+                println!("{}", tr!("wrong", answer = answer));
+                print_diff(&feedback, options, answer_direction);
+            }
+        }
+
+        // the blanked word is english in inverse mode, so there's nothing to define
+        if !inverse {
+            print_definitions(data, theme, &prompt.word, language);
+        }
+
+        if feedback.grade == Grade::Wrong && session.leech() {
+            println!("  {}", tr!("leech"));
+        }
+
+        if options.furigana {
+            if let Some(reading) = sentence.get_translation().and_then(|t| t.furigana()) {
+                println!("  {reading}");
+            }
         }
 
         // Old lookup logic
@@ -207,42 +526,206 @@ async fn start_game(
         // }
 
         println!();
+        session.advance()?;
     }
 
-    let new_correct = previous_correct + correct;
-    let new_total = total + len as i32;
+    Ok(())
+}
 
-    let message = if (new_total) / len as i32 == 1 {
-        format!("{}/{} sentences correct. Play again?", correct, len)
+// asks whether to play another round, showing the score so far
+fn ask_replay(summary: &Summary, terminal: Terminal) -> bool {
+    let message = if summary.rounds == 1 {
+        tr!(
+            "round-score",
+            correct = summary.correct,
+            total = summary.total
+        )
     } else {
-        format!(
-            "{}/{} sentences correct locally, {}/{} sentences correct overall. Play again?",
-            correct, len, new_correct, new_total
+        tr!(
+            "overall-score",
+            correct = summary.correct,
+            total = summary.total,
+            overall_correct = summary.overall_correct,
+            overall_total = summary.overall_total
         )
     };
 
-    let replay = Select::new(&message, vec!["No", "Yes"])
-        .without_help_message()
-        .prompt_skippable();
+    let yes = tr!("yes");
 
-    if let Ok(o) = replay {
-        if let Some(c) = o {
-            if c == "Yes" {
-                let sentences = generate_sentences(language.as_str()).await.unwrap();
-                let len = sentences.len();
-                start_game(sentences, len, language, new_correct, new_total, inverse).await;
+    if terminal.plain {
+        print!("{message} {} ", tr!("yes-no"));
+        let mut answer = String::new();
+        read_into(&mut answer);
+        // e.g. "o" or "oui" in french
+        let first = |text: &str| text.trim().to_lowercase().chars().next();
+        first(&answer).is_some() && first(&answer) == first(&yes)
+    } else {
+        let replay = Select::new(&message, vec![tr!("no"), yes.clone()])
+            .without_help_message()
+            .prompt_skippable();
+        matches!(replay, Ok(Some(answer)) if answer == yes)
+    }
+}
+
+// underlines the words (or characters, for unspaced text) the blanked word aligns with, as a
+// hint. align gets the normalised words and returns the positions to underline. outer is the
+// style the text is printed in, picked back up after each underlined word
+fn underline_aligned(
+    text: &str,
+    unspaced: bool,
+    theme: &Theme,
+    outer: &str,
+    align: impl Fn(&[String]) -> Option<Vec<usize>>,
+) -> String {
+    let units: Vec<String> = if unspaced {
+        text.chars().map(|c| c.to_string()).collect()
+    } else {
+        text.split(' ').map(str::to_string).collect()
+    };
+
+    let tokens: Vec<String> = units
+        .iter()
+        .map(|unit| remove_punctuation(unit).to_lowercase())
+        .collect();
+
+    let positions = align(&tokens).unwrap_or_default();
+
+    let marked: Vec<String> = units
+        .into_iter()
+        .enumerate()
+        .map(|(i, unit)| {
+            if positions.contains(&i) {
+                theme.paint_within(&theme.hint, &unit, outer)
             } else {
-                exit(0);
+                unit
+            }
+        })
+        .collect();
+
+    marked.join(if unspaced { "" } else { " " })
+}
+
+// shows the guess aligned with the word: wrong letters in red, missing ones in green. plain
+// mode says which letters were wrong instead
+fn print_diff(feedback: &Feedback, options: &GameOptions, direction: Direction) {
+    let theme = &options.theme;
+
+    // nothing useful to align when the guess was left blank
+    if feedback
+        .diff
+        .iter()
+        .all(|op| matches!(op, DiffOp::Missing(_)))
+    {
+        return;
+    }
+
+    let aligned = if options.terminal.plain {
+        describe_diff(&feedback.diff)
+    } else {
+        let mut aligned = String::new();
+
+        for op in &feedback.diff {
+            match op {
+                DiffOp::Same(c) => aligned.push(*c),
+                DiffOp::Substituted(guessed, _) => {
+                    aligned.push_str(&theme.paint(&theme.wrong, &guessed.to_string()))
+                }
+                DiffOp::Missing(expected) => {
+                    aligned.push_str(&theme.paint(&theme.missing, &expected.to_string()))
+                }
+                DiffOp::Extra(guessed) => {
+                    aligned.push_str(&theme.paint(&theme.extra, &guessed.to_string()))
+                }
             }
-        } else {
-            exit(0);
         }
+
+        bidi::isolate(&aligned, direction)
+    };
+
+    match feedback.describe() {
+        Some(reason) => println!("  {aligned} ({})", i18n::render(&reason)),
+        None => println!("  {aligned}"),
     }
 }
 
-// clear the screen and position cursor at the top left
-fn clear_screen() {
-    print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+// the differences in words, e.g. "letter 2 is a, not o; letter 5, t, is missing"
+fn describe_diff(diff: &[DiffOp]) -> String {
+    let mut mistakes = Vec::new();
+    // letters of the word so far
+    let mut position = 0;
+
+    for op in diff {
+        match op {
+            DiffOp::Same(_) => position += 1,
+            DiffOp::Substituted(guessed, expected) => {
+                position += 1;
+                mistakes.push(tr!(
+                    "letter-wrong",
+                    position = position,
+                    expected = expected.to_string(),
+                    guessed = guessed.to_string()
+                ));
+            }
+            DiffOp::Missing(expected) => {
+                position += 1;
+                mistakes.push(tr!(
+                    "letter-missing",
+                    position = position,
+                    expected = expected.to_string()
+                ));
+            }
+            DiffOp::Extra(guessed) => mistakes.push(tr!(
+                "letter-extra",
+                position = position,
+                guessed = guessed.to_string()
+            )),
+        }
+    }
+
+    mistakes.join("; ")
+}
+
+// the dictionary setting picks where words link to, see dictionary::provider_by_name
+fn pick_dictionary(name: &str) -> Box<dyn DictionaryProvider> {
+    provider_by_name(name).unwrap_or_else(|| {
+        Box::new(Wiktionary {
+            edition: String::from("en"),
+        })
+    })
+}
+
+// prints a line per part of speech, e.g. "noun (m) /ʃa/: cat; tomcat"
+fn print_definitions(data: &LanguageData, theme: &Theme, word: &str, language: &str) {
+    for entry in lookup_first(&data.local, word, language) {
+        println!(
+            "  {} {}",
+            theme.paint(&theme.label, &bidi::isolate_auto(word.trim())),
+            entry.short()
+        );
+    }
+}
+
+// one line per word, "?" for words none of the dictionaries know
+fn print_glosses(data: &LanguageData, theme: &Theme, text: &str, language: &str) {
+    if data.local.is_empty() {
+        println!("  {}", tr!("no-dictionaries"));
+        return;
+    }
+
+    for (word, definitions) in gloss_words(&data.local, text, language) {
+        let word = theme.paint(&theme.label, &bidi::isolate_auto(&word));
+        let gloss = definitions
+            .iter()
+            .map(|d| d.short())
+            .collect::<Vec<_>>()
+            .join(" | ");
+
+        if gloss.is_empty() {
+            println!("  {word} ?");
+        } else {
+            println!("  {word} {gloss}");
+        }
+    }
 }
 
 // user input
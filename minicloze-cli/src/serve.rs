@@ -0,0 +1,358 @@
+// `minicloze serve --stdio`: the game over newline-delimited JSON, for editor plugins and bots
+// which would rather not scrape the terminal. every line read is a request, named by its
+// "request" field, and gets one line back, named by its "response" field:
+//
+//     {"id": 1, "request": "start", "language": "french"}
+//     {"id": 1, "response": "round", "sentences": 10, "prompt": {"index": 0, ...}}
+//
+// an "id" is optional and copied onto the response. see README.md for every request
+
+use std::io::{self, BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use minicloze_lib::{
+    config::Config,
+    definitions::Definition,
+    dictionary::{lookup_first, url_or_wiktionary},
+    grading::Feedback,
+    missed::MissFilter,
+    sentence::{remove_punctuation, Strategy},
+    session::{GameSession, Phase, PromptView, SessionOptions, Source, Summary},
+};
+
+use crate::{commands::resolve_language, i18n, load_round, LanguageData};
+
+#[derive(Deserialize)]
+struct Line {
+    id: Option<Value>,
+    #[serde(flatten)]
+    request: Request,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+enum Request {
+    // a new game, in place of any other. options left out fall back to the config file
+    Start {
+        language: String,
+        native: Option<String>,
+        inverse: Option<bool>,
+        round_size: Option<usize>,
+        strategy: Option<Strategy>,
+        source: Option<Source>,
+        #[serde(default)]
+        review: bool,
+        seed: Option<u64>,
+    },
+    Prompt,
+    Answer {
+        guess: String,
+    },
+    // on to the next prompt, or the end of the round
+    Next,
+    // defines a word, in the game's language unless another is given
+    Lookup {
+        word: String,
+        language: Option<String>,
+    },
+    Summary,
+    // another round once one's finished
+    Replay,
+}
+
+#[derive(Serialize)]
+struct Reply {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<Value>,
+    #[serde(flatten)]
+    response: Response,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "response", rename_all = "snake_case")]
+enum Response {
+    // a round was loaded, by start or replay
+    Round {
+        language: String,
+        native: String,
+        sentences: usize,
+        prompt: Option<PromptView>,
+    },
+    // None when the round is over
+    Prompt {
+        phase: Phase,
+        prompt: Option<PromptView>,
+    },
+    Answer {
+        word: String,
+        guess: String,
+        feedback: Feedback,
+        // what went wrong, in words
+        description: Option<String>,
+        // whether the word keeps being forgotten, see srs.rs
+        leech: bool,
+    },
+    Definitions {
+        word: String,
+        definitions: Vec<Definition>,
+        url: String,
+    },
+    Summary {
+        summary: Summary,
+    },
+    Error {
+        message: String,
+    },
+}
+
+// the game being played, if one's started
+struct Game {
+    session: GameSession,
+    data: LanguageData,
+}
+
+// answers requests until standard input closes
+pub async fn stdio() -> Result<(), String> {
+    let config = Config::load()?;
+    let mut game = None;
+    let mut stdout = io::stdout();
+
+    for line in io::stdin().lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match serde_json::from_str::<Line>(&line) {
+            Ok(Line { id, request }) => Reply {
+                id,
+                response: handle(request, &mut game, &config)
+                    .await
+                    .unwrap_or_else(|message| Response::Error { message }),
+            },
+            Err(e) => Reply {
+                id: None,
+                response: Response::Error {
+                    message: e.to_string(),
+                },
+            },
+        };
+
+        let json = serde_json::to_string(&reply).map_err(|e| e.to_string())?;
+        writeln!(stdout, "{json}").map_err(|e| e.to_string())?;
+        stdout.flush().map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
+async fn handle(
+    request: Request,
+    game: &mut Option<Game>,
+    config: &Config,
+) -> Result<Response, String> {
+    match request {
+        Request::Start {
+            language,
+            native,
+            inverse,
+            round_size,
+            strategy,
+            source,
+            review,
+            seed,
+        } => {
+            let language = resolve_language(&language)?;
+            let settings = config.settings(&language);
+
+            let options = SessionOptions {
+                native: resolve_language(native.as_ref().unwrap_or(&settings.native))?,
+                // review rounds blank the words missed before, which are never english
+                inverse: !review && inverse.unwrap_or(settings.inverse),
+                round_size: round_size.unwrap_or(settings.round_size),
+                strategy: strategy.unwrap_or(settings.strategy),
+                source: source.unwrap_or(if review {
+                    Source::Cache
+                } else {
+                    Source::Tatoeba
+                }),
+                review,
+                filter: MissFilter::default(),
+                grading: settings.grading,
+                scheduler: settings.scheduler,
+                language,
+            };
+
+            let mut session = GameSession::new(options, seed);
+            let sentences = load_round(&mut session).await?;
+            let data = LanguageData::load(&session.options().language, &settings.dictionary);
+
+            let started = game.insert(Game { session, data });
+            Ok(round(&started.session, sentences))
+        }
+        Request::Prompt => {
+            let Game { session, .. } = playing(game)?;
+            Ok(Response::Prompt {
+                phase: session.phase(),
+                prompt: session.view(),
+            })
+        }
+        Request::Answer { guess } => {
+            let Game { session, data } = playing(game)?;
+            let feedback = session.submit(&guess, data.lexicon.as_ref())?;
+            let word = session
+                .current()
+                .map(|(_, prompt)| prompt.word.trim().to_string())
+                .unwrap_or_default();
+
+            let description = feedback.describe().as_ref().map(i18n::render);
+
+            Ok(Response::Answer {
+                word,
+                guess,
+                feedback,
+                description,
+                leech: session.leech(),
+            })
+        }
+        Request::Next => {
+            let Game { session, .. } = playing(game)?;
+            session.advance()?;
+            Ok(Response::Prompt {
+                phase: session.phase(),
+                prompt: session.view(),
+            })
+        }
+        // looking words up doesn't need a game if the language is given
+        Request::Lookup {
+            word,
+            language: Some(language),
+        } => {
+            let language = resolve_language(&language)?;
+            let settings = config.settings(&language);
+            let data = LanguageData::load(&language, &settings.dictionary);
+            Ok(lookup(&data, &word, &language))
+        }
+        Request::Lookup {
+            word,
+            language: None,
+        } => {
+            let Game { session, data } = playing(game)?;
+            Ok(lookup(data, &word, &session.options().language))
+        }
+        Request::Summary => {
+            let Game { session, .. } = playing(game)?;
+            Ok(Response::Summary {
+                summary: session.summary(),
+            })
+        }
+        Request::Replay => {
+            let Game { session, .. } = playing(game)?;
+
+            // the round is loaded on a copy, so a game with nothing left to play stays finished
+            let mut next = session.clone();
+            next.replay()?;
+            let sentences = load_round(&mut next).await?;
+            *session = next;
+
+            Ok(round(session, sentences))
+        }
+    }
+}
+
+fn playing(game: &mut Option<Game>) -> Result<&mut Game, String> {
+    game.as_mut().ok_or(String::from("Start a game first"))
+}
+
+fn round(session: &GameSession, sentences: usize) -> Response {
+    Response::Round {
+        language: session.options().language.clone(),
+        native: session.options().native.clone(),
+        sentences,
+        prompt: session.view(),
+    }
+}
+
+fn lookup(data: &LanguageData, word: &str, language: &str) -> Response {
+    let word = remove_punctuation(word.trim());
+
+    Response::Definitions {
+        definitions: lookup_first(&data.local, &word, language),
+        url: url_or_wiktionary(data.dictionary.as_ref(), &word, language),
+        word,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use minicloze_lib::{
+        cache,
+        sentence::{Sentence, Translation},
+    };
+
+    use super::*;
+
+    // what handle answers a request line with, as it would be written out
+    async fn send(line: Value, game: &mut Option<Game>) -> Value {
+        let Line { request, .. } = serde_json::from_value(line).unwrap();
+        let response = handle(request, game, &Config::default())
+            .await
+            .unwrap_or_else(|message| Response::Error { message });
+
+        serde_json::to_value(response).unwrap()
+    }
+
+    #[tokio::test]
+    async fn a_review_with_nothing_left_stays_finished() {
+        let dir = std::env::temp_dir().join(format!("minicloze-serve-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::env::set_var("XDG_CONFIG_HOME", &dir);
+        std::env::set_var("XDG_DATA_HOME", &dir);
+
+        let sentence = Sentence {
+            id: 1,
+            text: String::from("cat"),
+            translations: vec![Translation {
+                id: 2,
+                text: String::from("chat"),
+                transcriptions: Vec::new(),
+            }],
+        };
+        cache::store("fra", "eng", &[sentence]).unwrap();
+
+        let mut game = None;
+        assert_eq!(
+            send(json!({"request": "prompt"}), &mut game).await["response"],
+            "error"
+        );
+
+        // a miss to review
+        let start = json!({"request": "start", "language": "fr", "native": "eng", "source": "cache", "round_size": 1});
+        assert_eq!(send(start, &mut game).await["sentences"], 1);
+        send(json!({"request": "answer", "guess": "chien"}), &mut game).await;
+        send(json!({"request": "next"}), &mut game).await;
+
+        let review = json!({"request": "start", "language": "fr", "native": "eng", "review": true});
+        assert_eq!(send(review, &mut game).await["sentences"], 1);
+        let answer = send(json!({"request": "answer", "guess": "chat"}), &mut game).await;
+        assert_eq!(answer["feedback"]["grade"], "Correct");
+        let next = send(json!({"request": "next"}), &mut game).await;
+        assert_eq!(next["phase"], "finished");
+
+        // the miss was put right, so there's nothing to replay
+        let replay = send(json!({"request": "replay"}), &mut game).await;
+        assert_eq!(replay["response"], "error");
+        let prompt = send(json!({"request": "prompt"}), &mut game).await;
+        assert_eq!(prompt["phase"], "finished");
+        assert_eq!(
+            send(json!({"request": "summary"}), &mut game).await["summary"]["rounds"],
+            1
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
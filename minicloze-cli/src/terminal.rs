@@ -0,0 +1,90 @@
+// what the terminal can show. plain mode is for screen readers and terminals which don't
+// understand escape sequences: no colours, links or clearing the screen, the blank is spelled
+// out and menus are typed answers. hyperlinks (OSC 8) are only printed where the terminal is
+// known to support them, others show the sequences as garbage
+
+use std::env;
+use std::io::{stdout, IsTerminal};
+
+use terminal_link::Link;
+
+use crate::i18n::tr;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Terminal {
+    pub plain: bool,
+    pub hyperlinks: bool,
+}
+
+impl Terminal {
+    pub fn detect(plain: bool) -> Terminal {
+        Terminal {
+            plain,
+            hyperlinks: !plain && supports_hyperlinks(),
+        }
+    }
+
+    // text linking to url, or just the text
+    pub fn link(&self, text: &str, url: &str) -> String {
+        if self.hyperlinks {
+            Link::new(text, url).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    // clear the screen and position cursor at the top left
+    pub fn clear_screen(&self) {
+        if !self.plain {
+            print!("{esc}[2J{esc}[1;1H", esc = 27 as char);
+        }
+    }
+
+    // what's read out in place of the blank, its length if it's shown
+    pub fn blank(&self, letters: Option<usize>) -> String {
+        if !self.plain {
+            return match letters {
+                Some(letters) => "_".repeat(letters),
+                None => String::from("?"),
+            };
+        }
+        match letters {
+            Some(letters) => tr!("blank-letters", count = letters),
+            None => tr!("blank"),
+        }
+    }
+}
+
+// FORCE_HYPERLINK=1 or 0 overrides the guess, otherwise it's the terminals known to support
+// them, going by the variables they set
+fn supports_hyperlinks() -> bool {
+    if let Ok(force) = env::var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+
+    if !stdout().is_terminal() {
+        return false;
+    }
+
+    let set = |name: &str| env::var_os(name).is_some_and(|value| !value.is_empty());
+
+    if set("DOMTERM") || set("WT_SESSION") || set("KONSOLE_VERSION") {
+        return true;
+    }
+
+    // gnome terminal and other VTE terminals from 0.50
+    if let Ok(version) = env::var("VTE_VERSION") {
+        return version.parse::<u32>().is_ok_and(|version| version >= 5000);
+    }
+
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
+
+    matches!(
+        program.as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "Hyper" | "ghostty" | "rio" | "Tabby"
+    ) || matches!(
+        term.as_str(),
+        "xterm-kitty" | "alacritty" | "foot" | "foot-extra" | "xterm-ghostty" | "wezterm"
+    )
+}
@@ -0,0 +1,247 @@
+// colour themes. each part of the game gets a style written like "bold black on bright-white":
+// bold, dim, italic, underline and reverse, a foreground colour, and "on" a background colour.
+// colours are the eight ANSI names, optionally "bright-", a 256-colour number or #rrggbb.
+// users can define their own themes in config.toml, starting from a built-in one, e.g.
+//
+//     theme = "mine"
+//
+//     [themes.mine]
+//     base = "light"
+//     correct = "bold blue"
+//
+// NO_COLOR (https://no-color.org), plain mode or output that isn't a terminal gets no styling at
+// all
+
+use std::collections::BTreeMap;
+use std::env;
+use std::io::{stdout, IsTerminal};
+
+use minicloze_lib::config::Config;
+
+const RESET: &str = "\x1B[0m";
+
+// the built-in themes, in the order the config editor shows them
+pub const BUILT_IN: [&str; 4] = ["dark", "light", "high-contrast", "colour-blind"];
+
+// escape sequences for each part of the game, empty for unstyled
+#[derive(Clone, Debug, Default)]
+pub struct Theme {
+    // the sentence being filled in
+    pub sentence: String,
+    // "FRA:" before sentences, and other headings
+    pub label: String,
+    pub blank: String,
+    pub correct: String,
+    pub close: String,
+    pub wrong: String,
+    // in the diff of a guess, letters it left out
+    pub missing: String,
+    // in the diff of a guess, letters it shouldn't have
+    pub extra: String,
+    // the translation's word the blank most likely corresponds to
+    pub hint: String,
+}
+
+// the keys a theme sets, in the order of Theme's fields
+const PARTS: [&str; 9] = [
+    "sentence", "label", "blank", "correct", "close", "wrong", "missing", "extra", "hint",
+];
+
+fn built_in(name: &str) -> Option<[&'static str; 9]> {
+    Some(match name {
+        "dark" => [
+            "black on bright-white",
+            "bold",
+            "black on bright-white",
+            "white on green",
+            "bold bright-white on yellow",
+            "bold bright-white on red",
+            "underline green",
+            "bold red",
+            "underline",
+        ],
+        "light" => [
+            "bright-white on black",
+            "bold",
+            "bright-white on black",
+            "bright-white on green",
+            "black on bright-yellow",
+            "bright-white on red",
+            "underline blue",
+            "bold red",
+            "underline",
+        ],
+        "high-contrast" => [
+            "bold bright-white on black",
+            "bold underline",
+            "bold bright-white on black",
+            "bold black on bright-green",
+            "bold black on bright-yellow",
+            "bold bright-white on bright-red",
+            "bold underline bright-green",
+            "bold reverse",
+            "bold underline",
+        ],
+        // blue and orange, which are told apart with any colour vision deficiency
+        "colour-blind" => [
+            "black on bright-white",
+            "bold",
+            "black on bright-white",
+            "bold bright-white on blue",
+            "bold black on 220",
+            "bold black on 208",
+            "underline blue",
+            "bold 208",
+            "underline",
+        ],
+        _ => return None,
+    })
+}
+
+impl Theme {
+    // the theme picked in the config, or none when colours are off
+    pub fn load(config: &Config) -> Result<Theme, String> {
+        if no_color() || config.plain || !stdout().is_terminal() {
+            return Ok(Theme::default());
+        }
+
+        Theme::named(&config.theme, &config.themes)
+    }
+
+    pub fn named(
+        name: &str,
+        custom: &BTreeMap<String, BTreeMap<String, String>>,
+    ) -> Result<Theme, String> {
+        let mut specs: BTreeMap<&str, String> = BTreeMap::new();
+
+        match (built_in(name), custom.get(name)) {
+            (Some(parts), _) => {
+                specs.extend(PARTS.into_iter().zip(parts.map(str::to_string)));
+            }
+            (None, Some(theme)) => {
+                let base = theme.get("base").map_or("dark", String::as_str);
+                let parts = built_in(base).ok_or_else(|| {
+                    format!("Theme {name} is based on {base}, which isn't built in")
+                })?;
+
+                specs.extend(PARTS.into_iter().zip(parts.map(str::to_string)));
+
+                for (part, spec) in theme {
+                    if part == "base" {
+                        continue;
+                    }
+                    let part = PARTS.iter().find(|p| *p == part).ok_or_else(|| {
+                        format!("Theme {name} sets {part}, which isn't a part of a theme")
+                    })?;
+                    specs.insert(part, spec.clone());
+                }
+            }
+            (None, None) => return Err(format!("There's no theme called {name}")),
+        }
+
+        let style =
+            |part: &str| parse(&specs[part]).map_err(|e| format!("Theme {name}, {part}: {e}"));
+
+        Ok(Theme {
+            sentence: style("sentence")?,
+            label: style("label")?,
+            blank: style("blank")?,
+            correct: style("correct")?,
+            close: style("close")?,
+            wrong: style("wrong")?,
+            missing: style("missing")?,
+            extra: style("extra")?,
+            hint: style("hint")?,
+        })
+    }
+
+    // text in a style, resetting everything afterwards
+    pub fn paint(&self, style: &str, text: &str) -> String {
+        self.paint_within(style, text, "")
+    }
+
+    // text in a style inside text in another style, e.g. the hint in a styled sentence
+    pub fn paint_within(&self, style: &str, text: &str, outer: &str) -> String {
+        if style.is_empty() {
+            return text.to_string();
+        }
+        format!("{style}{text}{RESET}{outer}")
+    }
+}
+
+// whether NO_COLOR is set to anything
+pub fn no_color() -> bool {
+    env::var("NO_COLOR").is_ok_and(|value| !value.is_empty())
+}
+
+// a style spec as an SGR escape sequence
+fn parse(spec: &str) -> Result<String, String> {
+    let mut codes: Vec<String> = Vec::new();
+    let mut background = false;
+
+    for word in spec.split_whitespace() {
+        let code = match word {
+            "on" => {
+                background = true;
+                continue;
+            }
+            "bold" => String::from("1"),
+            "dim" => String::from("2"),
+            "italic" => String::from("3"),
+            "underline" => String::from("4"),
+            "reverse" => String::from("7"),
+            colour => {
+                let code = colour_code(colour, background)
+                    .ok_or_else(|| format!("{colour} isn't a colour or style"))?;
+                background = false;
+                code
+            }
+        };
+        codes.push(code);
+    }
+
+    if codes.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format!("\x1B[{}m", codes.join(";")))
+}
+
+fn colour_code(colour: &str, background: bool) -> Option<String> {
+    const NAMES: [&str; 8] = [
+        "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+    ];
+
+    let (bright, name) = match colour.strip_prefix("bright-") {
+        Some(name) => (true, name),
+        None => (false, colour),
+    };
+
+    if let Some(index) = NAMES.iter().position(|n| *n == name) {
+        let base = match (bright, background) {
+            (false, false) => 30,
+            (false, true) => 40,
+            (true, false) => 90,
+            (true, true) => 100,
+        };
+        return Some((base + index).to_string());
+    }
+
+    let select = if background { 48 } else { 38 };
+
+    if let Ok(number) = colour.parse::<u8>() {
+        return Some(format!("{select};5;{number}"));
+    }
+
+    // a byte length of 6 can still split a multibyte character
+    let hex = colour
+        .strip_prefix('#')
+        .filter(|hex| hex.len() == 6 && hex.is_ascii())?;
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+    Some(format!(
+        "{select};2;{};{};{}",
+        channel(0)?,
+        channel(2)?,
+        channel(4)?
+    ))
+}
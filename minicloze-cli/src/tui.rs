@@ -0,0 +1,499 @@
+// the full-screen frontend, `minicloze play --tui`. the prompt is in the middle with the other
+// sentence below it, a gauge above shows how far through the round you are, and underneath are
+// the round's answers and a side panel defining a word of the sentence from the offline
+// dictionaries. up and down step back through the round's earlier prompts, left and right pick
+// the word to define
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Alignment, Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Gauge, List, ListItem, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use minicloze_lib::{
+    dictionary::gloss_words,
+    grading::{Feedback, Grade},
+    sentence::{Prompt, Sentence},
+    session::{GameSession, Phase},
+};
+
+use crate::i18n::{self, tr};
+use crate::theme::Theme;
+use crate::LanguageData;
+
+// a prompt of this round which has been answered
+struct Answer {
+    sentence: Sentence,
+    prompt: Prompt,
+    guess: String,
+    feedback: Feedback,
+}
+
+struct App<'a> {
+    data: &'a LanguageData,
+    answers: Vec<Answer>,
+    // the guess being typed
+    input: String,
+    // which prompt is shown, an index into answers, or answers.len() for the one being answered
+    // or the round's summary
+    shown: usize,
+    // the word of the shown sentence which is defined in the side panel
+    word: usize,
+    theme: &'a Theme,
+}
+
+// what a key press leads to
+enum Action {
+    Continue,
+    Replay,
+    Quit,
+}
+
+pub async fn run(
+    session: &mut GameSession,
+    data: &LanguageData,
+    theme: &Theme,
+) -> Result<(), String> {
+    let mut terminal = ratatui::init();
+
+    let mut app = App {
+        data,
+        answers: Vec::new(),
+        input: String::new(),
+        shown: 0,
+        word: 0,
+        theme,
+    };
+
+    let result = app.run(&mut terminal, session).await;
+
+    ratatui::restore();
+    result
+}
+
+impl App<'_> {
+    async fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        session: &mut GameSession,
+    ) -> Result<(), String> {
+        loop {
+            terminal
+                .draw(|frame| self.draw(frame, session))
+                .map_err(|e| e.to_string())?;
+
+            let Event::Key(key) = event::read().map_err(|e| e.to_string())? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match self.handle(key, session)? {
+                Action::Continue => {}
+                Action::Quit => return Ok(()),
+                Action::Replay => {
+                    session.replay()?;
+                    self.answers.clear();
+                    self.shown = 0;
+                    self.word = 0;
+
+                    terminal
+                        .draw(|frame| {
+                            let loading =
+                                Paragraph::new(tr!("fetching")).alignment(Alignment::Center);
+                            frame.render_widget(loading, centred(frame.area(), 1));
+                        })
+                        .map_err(|e| e.to_string())?;
+
+                    crate::load_round(session).await?;
+                }
+            }
+        }
+    }
+
+    fn handle(&mut self, key: KeyEvent, session: &mut GameSession) -> Result<Action, String> {
+        let latest = self.answers.len();
+        let last = self.last(session.phase());
+        let answering = session.phase() == Phase::Prompting && self.shown == last;
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Ok(Action::Quit)
+            }
+            KeyCode::Esc if self.shown != last => self.show(last),
+            KeyCode::Esc => return Ok(Action::Quit),
+            KeyCode::Up => self.show(self.shown.saturating_sub(1)),
+            KeyCode::Down => self.show((self.shown + 1).min(last)),
+            KeyCode::Left => self.word = self.word.saturating_sub(1),
+            KeyCode::Right => self.word += 1,
+            KeyCode::Backspace if answering => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) if answering => self.input.push(c),
+            KeyCode::Enter if self.shown != last => self.show(last),
+            KeyCode::Enter => match session.phase() {
+                Phase::Prompting => {
+                    let (sentence, prompt) = session.current().cloned().unwrap();
+                    let feedback = session.submit(&self.input, self.data.lexicon.as_ref())?;
+
+                    self.answers.push(Answer {
+                        sentence,
+                        prompt,
+                        guess: std::mem::take(&mut self.input),
+                        feedback,
+                    });
+                    self.show(latest);
+                }
+                Phase::Answered => {
+                    session.advance()?;
+                    self.show(self.last(session.phase()));
+                }
+                Phase::Finished => return Ok(Action::Replay),
+                Phase::Loading => {}
+            },
+            _ => {}
+        }
+
+        Ok(Action::Continue)
+    }
+
+    // what's shown when not looking back: the prompt being answered, the one just answered, or
+    // after the last answer, the round's summary
+    fn last(&self, phase: Phase) -> usize {
+        match phase {
+            Phase::Answered => self.answers.len().saturating_sub(1),
+            Phase::Loading | Phase::Prompting | Phase::Finished => self.answers.len(),
+        }
+    }
+
+    fn show(&mut self, shown: usize) {
+        if shown != self.shown {
+            self.word = 0;
+        }
+        self.shown = shown;
+    }
+
+    fn grade_style(&self, grade: Grade) -> Style {
+        let theme = self.theme;
+        style(match grade {
+            Grade::Correct => &theme.correct,
+            Grade::Close => &theme.close,
+            Grade::Wrong => &theme.wrong,
+        })
+    }
+
+    fn draw(&mut self, frame: &mut Frame, session: &GameSession) {
+        let [gauge, prompt, bottom, help] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(8),
+            Constraint::Length(12),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [history, gloss] =
+            Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)])
+                .areas(bottom);
+
+        self.draw_gauge(frame, gauge, session);
+        self.draw_prompt(frame, prompt, session);
+        self.draw_history(frame, history);
+        self.draw_gloss(frame, gloss, session);
+
+        let help_line = Paragraph::new(tr!("tui-help"))
+            .style(Style::new().add_modifier(Modifier::DIM))
+            .alignment(Alignment::Center);
+        frame.render_widget(help_line, help);
+    }
+
+    fn draw_gauge(&self, frame: &mut Frame, area: Rect, session: &GameSession) {
+        let total = session.round().len();
+        let answered = self.answers.len();
+        let correct = self
+            .answers
+            .iter()
+            .filter(|answer| answer.feedback.grade == Grade::Correct)
+            .count();
+
+        let ratio = if total == 0 {
+            0.0
+        } else {
+            answered as f64 / total as f64
+        };
+
+        let gauge = Gauge::default()
+            .block(Block::bordered().title(Span::styled(
+                session.options().language.to_uppercase(),
+                style(&self.theme.label),
+            )))
+            .gauge_style(style(&self.theme.correct))
+            .ratio(ratio.min(1.0))
+            .label(tr!(
+                "tui-progress",
+                answered = answered,
+                total = total,
+                correct = correct
+            ));
+
+        frame.render_widget(gauge, area);
+    }
+
+    fn draw_prompt(&self, frame: &mut Frame, area: Rect, session: &GameSession) {
+        let block = Block::bordered();
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        if session.phase() == Phase::Finished && self.shown == self.last(Phase::Finished) {
+            let summary = session.summary();
+            let message = if summary.rounds == 1 {
+                tr!(
+                    "round-score",
+                    correct = summary.correct,
+                    total = summary.total
+                )
+            } else {
+                tr!(
+                    "overall-score",
+                    correct = summary.correct,
+                    total = summary.total,
+                    overall_correct = summary.overall_correct,
+                    overall_total = summary.overall_total
+                )
+            };
+            let lines = vec![
+                Line::from(message).style(style(&self.theme.label)),
+                Line::from(""),
+                Line::from(tr!("tui-replay")),
+            ];
+            let paragraph = Paragraph::new(lines)
+                .alignment(Alignment::Center)
+                .wrap(Wrap { trim: true });
+            frame.render_widget(paragraph, centred(inner, 3));
+            return;
+        }
+
+        let inverse = session.options().inverse;
+        let language = &session.options().language;
+
+        let (sentence, prompt, answer) = match self.answers.get(self.shown) {
+            Some(answer) => (&answer.sentence, &answer.prompt, Some(answer)),
+            None => match session.current() {
+                Some((sentence, prompt)) => (sentence, prompt, None),
+                None => return,
+            },
+        };
+
+        let blank = match answer {
+            Some(answer) => {
+                Span::styled(prompt.word.clone(), self.grade_style(answer.feedback.grade))
+            }
+            None => {
+                // the length would give the english word away
+                let length = if inverse {
+                    self.input.chars().count() + 1
+                } else {
+                    prompt.word.chars().count()
+                };
+                let padding = length.saturating_sub(self.input.chars().count());
+                Span::styled(
+                    format!("{}{}", self.input, "_".repeat(padding)),
+                    style(&self.theme.blank),
+                )
+            }
+        };
+
+        // the other sentence is in the native language, or in the target one in inverse mode
+        let other = if inverse {
+            sentence.get_translation().map(|t| t.text.clone())
+        } else {
+            Some(sentence.text.clone())
+        };
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(prompt.first_half.clone(), style(&self.theme.sentence)),
+                blank,
+                Span::styled(
+                    format!("{}{}", prompt.separator(language), prompt.second_half),
+                    style(&self.theme.sentence),
+                ),
+            ]),
+            Line::from(other.unwrap_or_default()).style(Style::new().add_modifier(Modifier::DIM)),
+        ];
+
+        if let Some(answer) = answer {
+            let feedback = &answer.feedback;
+            let key = match feedback.grade {
+                Grade::Correct => "correct",
+                Grade::Close => "close",
+                Grade::Wrong => "wrong",
+            };
+
+            lines.push(Line::from(""));
+            lines.push(
+                Line::from(tr!(key, answer = prompt.word.trim().to_string()))
+                    .style(self.grade_style(feedback.grade)),
+            );
+
+            if feedback.grade != Grade::Correct {
+                lines.push(Line::from(tr!(
+                    "tui-guess",
+                    guess = answer.guess.trim().to_string()
+                )));
+            }
+
+            if let Some(reason) = feedback.describe() {
+                lines.push(Line::from(format!("({})", i18n::render(&reason))));
+            }
+        }
+
+        let height = lines.len() as u16;
+        let paragraph = Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, centred(inner, height));
+    }
+
+    fn draw_history(&self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .answers
+            .iter()
+            .enumerate()
+            .map(|(i, answer)| {
+                let word = answer.prompt.word.trim();
+                let text = match answer.feedback.grade {
+                    Grade::Correct => format!("{}. {word}", i + 1),
+                    _ => format!("{}. {} → {word}", i + 1, answer.guess.trim()),
+                };
+                ListItem::new(text).style(self.grade_style(answer.feedback.grade))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::bordered().title(tr!("tui-history")))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+        let mut state = ListState::default();
+        if self.shown < self.answers.len() {
+            state.select(Some(self.shown));
+        }
+
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    fn draw_gloss(&mut self, frame: &mut Frame, area: Rect, session: &GameSession) {
+        let block = Block::bordered().title(tr!("tui-dictionary"));
+        let language = &session.options().language;
+        let inverse = session.options().inverse;
+
+        // the words of the sentence in the language being learnt, without the blank before
+        // it's answered
+        let words = match (self.answers.get(self.shown), session.current()) {
+            (Some(answer), _) => match answer.sentence.get_translation() {
+                Some(translation) => gloss_words(&self.data.local, &translation.text, language),
+                None => Vec::new(),
+            },
+            (None, Some((sentence, prompt))) => {
+                if inverse {
+                    let text = sentence.get_translation().map_or("", |t| t.text.as_str());
+                    gloss_words(&self.data.local, text, language)
+                } else {
+                    let mut words = gloss_words(&self.data.local, &prompt.first_half, language);
+                    words.extend(gloss_words(&self.data.local, &prompt.second_half, language));
+                    words
+                }
+            }
+            (None, None) => Vec::new(),
+        };
+
+        if words.is_empty() {
+            frame.render_widget(block, area);
+            return;
+        }
+
+        self.word = self.word.min(words.len() - 1);
+        let (word, definitions) = &words[self.word];
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled(word.clone(), style(&self.theme.label)),
+                Span::styled(
+                    format!("  {}/{}", self.word + 1, words.len()),
+                    Style::new().add_modifier(Modifier::DIM),
+                ),
+            ]),
+            Line::from(""),
+        ];
+
+        if self.data.local.is_empty() {
+            lines.push(Line::from(tr!("no-dictionaries")));
+        } else if definitions.is_empty() {
+            lines.push(Line::from(tr!("tui-no-definitions")));
+        }
+        lines.extend(definitions.iter().map(|d| Line::from(d.short())));
+
+        let paragraph = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, area);
+    }
+}
+
+// a theme's escape sequence as a ratatui style, so the full-screen interface is in the same
+// colours as the rest. see theme::parse for the codes it's made of
+fn style(sgr: &str) -> Style {
+    let Some(codes) = sgr
+        .strip_prefix("\x1B[")
+        .and_then(|codes| codes.strip_suffix('m'))
+    else {
+        return Style::new();
+    };
+
+    let mut codes = codes.split(';').filter_map(|code| code.parse::<u8>().ok());
+    let mut style = Style::new();
+
+    while let Some(code) = codes.next() {
+        style = match code {
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            7 => style.add_modifier(Modifier::REVERSED),
+            // the first 16 of the 256 colours are the named ones
+            30..=37 => style.fg(Color::Indexed(code - 30)),
+            40..=47 => style.bg(Color::Indexed(code - 40)),
+            90..=97 => style.fg(Color::Indexed(code - 90 + 8)),
+            100..=107 => style.bg(Color::Indexed(code - 100 + 8)),
+            38 | 48 => {
+                let colour = match codes.next() {
+                    Some(5) => codes.next().map(Color::Indexed),
+                    Some(2) => match (codes.next(), codes.next(), codes.next()) {
+                        (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r, g, b)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match (colour, code) {
+                    (Some(colour), 38) => style.fg(colour),
+                    (Some(colour), _) => style.bg(colour),
+                    (None, _) => style,
+                }
+            }
+            _ => style,
+        };
+    }
+
+    style
+}
+
+// a band of the given height in the middle of the area
+fn centred(area: Rect, height: u16) -> Rect {
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(height.min(area.height)),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+    middle
+}
@@ -0,0 +1,707 @@
+// the screens of the desktop frontend: picking a language, a card per sentence, and the
+// round's results, all drawn by App::ui. rounds are loaded on another thread so the window keeps
+// responding while tatoeba is fetched from
+
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use eframe::egui::{self, Color32, RichText};
+
+use minicloze_lib::{
+    config::Config,
+    dictionary::{
+        local_dictionaries, lookup_first, provider_by_name, url_or_wiktionary, DictionaryProvider,
+        Wiktionary,
+    },
+    grading::{Feedback, Grade},
+    i18n::render,
+    langs::Language,
+    missed::MissFilter,
+    morph::Lexicon,
+    resolve::resolve_language,
+    sentence::remove_punctuation,
+    session::{GameSession, Phase, SessionOptions, Source},
+};
+
+const CORRECT: Color32 = Color32::from_rgb(46, 160, 67);
+const CLOSE: Color32 = Color32::from_rgb(210, 153, 34);
+const WRONG: Color32 = Color32::from_rgb(218, 54, 51);
+
+pub struct App {
+    config: Config,
+    screen: Screen,
+    // the language picker's search
+    filter: String,
+    language: Option<&'static Language>,
+    inverse: bool,
+    round_size: usize,
+    source: Source,
+    // why the last round couldn't be started, shown on the picker
+    error: Option<String>,
+    // loads a round on the thread it's called on, see load
+    loader: fn(&mut GameSession) -> Result<usize, String>,
+}
+
+enum Screen {
+    Picker,
+    Loading(Receiver<Result<Game, String>>),
+    Card(Game),
+    Results(Game),
+}
+
+// a game being played, with the language's dictionaries
+struct Game {
+    session: GameSession,
+    // UniMorph lexicon, for inflection feedback
+    lexicon: Option<Lexicon>,
+    // wiktextract, StarDict and dictd dictionaries
+    local: Vec<Box<dyn DictionaryProvider>>,
+    dictionary: Box<dyn DictionaryProvider>,
+    answers: Vec<Answer>,
+    guess: String,
+    // the word clicked on, defined in the side panel
+    looked_up: Option<String>,
+}
+
+struct Answer {
+    word: String,
+    guess: String,
+    feedback: Feedback,
+}
+
+impl App {
+    pub fn new() -> App {
+        let config = Config::load().unwrap_or_default();
+        let language = config
+            .language
+            .as_deref()
+            .and_then(|language| resolve_language(language).ok());
+
+        App {
+            language,
+            inverse: config.inverse,
+            round_size: config.round_size,
+            source: Source::Tatoeba,
+            filter: String::new(),
+            error: None,
+            loader: load,
+            screen: Screen::Picker,
+            config,
+        }
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        let screen = std::mem::replace(&mut self.screen, Screen::Picker);
+
+        self.screen = match screen {
+            Screen::Picker => self.picker(ctx),
+            Screen::Loading(loading) => self.loading(ctx, loading),
+            Screen::Card(game) => self.card(ctx, game),
+            Screen::Results(game) => self.results(ctx, game),
+        };
+    }
+
+    fn picker(&mut self, ctx: &egui::Context) -> Screen {
+        let mut play = false;
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading("What language do you want to study?");
+            ui.add_space(8.0);
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text("Search, e.g. French or Français"),
+            );
+
+            let filter = self.filter.to_lowercase();
+
+            egui::ScrollArea::vertical()
+                .max_height(ui.available_height() - 120.0)
+                .show(ui, |ui| {
+                    // most sentences first
+                    for language in Language::all() {
+                        let name = language.display_name();
+                        if !name.to_lowercase().contains(&filter)
+                            && !language.code.contains(&filter)
+                        {
+                            continue;
+                        }
+
+                        let selected = self.language == Some(language);
+                        if ui.selectable_label(selected, name).clicked() {
+                            self.language = Some(language);
+                        }
+                    }
+                });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.inverse, false, "Fill in the target language");
+                ui.radio_value(&mut self.inverse, true, "Fill in your native language");
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.source, Source::Tatoeba, "Fresh from Tatoeba");
+                ui.radio_value(&mut self.source, Source::Cache, "Cached sentences");
+            });
+            ui.horizontal(|ui| {
+                ui.label("Sentences per round");
+                ui.add(egui::DragValue::new(&mut self.round_size).range(1..=100));
+            });
+
+            if let Some(error) = &self.error {
+                ui.colored_label(WRONG, error);
+            }
+
+            let button = egui::Button::new(match self.language {
+                Some(language) => format!("Play {}", language.name),
+                None => String::from("Pick a language"),
+            });
+            play = ui.add_enabled(self.language.is_some(), button).clicked();
+        });
+
+        match self.language {
+            Some(language) if play => self.start(language),
+            _ => Screen::Picker,
+        }
+    }
+
+    // loads the first round on another thread
+    fn start(&mut self, language: &'static Language) -> Screen {
+        self.error = None;
+        let settings = self.config.settings(language.code);
+
+        let native = match resolve_language(&settings.native) {
+            Ok(native) => native.code.to_string(),
+            Err(e) => {
+                self.error = Some(e);
+                return Screen::Picker;
+            }
+        };
+
+        let options = SessionOptions {
+            language: language.code.to_string(),
+            native,
+            inverse: self.inverse,
+            round_size: self.round_size,
+            strategy: settings.strategy,
+            source: self.source,
+            review: false,
+            filter: MissFilter::default(),
+            grading: settings.grading,
+            scheduler: settings.scheduler,
+        };
+        let dictionary = settings.dictionary;
+        let loader = self.loader;
+
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut session = GameSession::new(options, None);
+            let code = language.code;
+
+            let game = loader(&mut session).map(|_| Game {
+                session,
+                lexicon: Lexicon::load(code).unwrap_or(None),
+                local: local_dictionaries(code),
+                dictionary: provider_by_name(&dictionary).unwrap_or_else(|| {
+                    Box::new(Wiktionary {
+                        edition: String::from("en"),
+                    })
+                }),
+                answers: Vec::new(),
+                guess: String::new(),
+                looked_up: None,
+            });
+
+            let _ = sender.send(game);
+        });
+
+        Screen::Loading(receiver)
+    }
+
+    fn loading(&mut self, ctx: &egui::Context, loading: Receiver<Result<Game, String>>) -> Screen {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.centered_and_justified(|ui| {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Fetching sentences for you...");
+                });
+            });
+        });
+
+        match loading.try_recv() {
+            Ok(Ok(game)) => Screen::Card(game),
+            Ok(Err(e)) => {
+                self.error = Some(e);
+                Screen::Picker
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                // nothing else wakes the window up
+                ctx.request_repaint();
+                Screen::Loading(loading)
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.error = Some(String::from("Loading the round failed"));
+                Screen::Picker
+            }
+        }
+    }
+
+    fn card(&mut self, ctx: &egui::Context, mut game: Game) -> Screen {
+        let language = game.session.options().language.clone();
+        let inverse = game.session.options().inverse;
+
+        egui::SidePanel::right("dictionary")
+            .min_width(240.0)
+            .show(ctx, |ui| {
+                ui.heading("Dictionary");
+                ui.separator();
+
+                let Some(word) = &game.looked_up else {
+                    ui.label("Click a word to look it up.");
+                    return;
+                };
+
+                ui.label(RichText::new(word).strong().size(18.0));
+
+                let definitions = lookup_first(&game.local, word, &language);
+                if game.local.is_empty() {
+                    ui.label("No offline dictionaries for this language.");
+                } else if definitions.is_empty() {
+                    ui.label("No definitions.");
+                }
+                for definition in definitions {
+                    ui.label(definition.short());
+                }
+
+                ui.add_space(8.0);
+                let url = url_or_wiktionary(game.dictionary.as_ref(), word, &language);
+                ui.hyperlink_to("Open in the online dictionary", url);
+            });
+
+        let mut advance = false;
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let total = game.session.round().len();
+            let answered = game.answers.len();
+            let correct = game
+                .answers
+                .iter()
+                .filter(|answer| answer.feedback.grade == Grade::Correct)
+                .count();
+
+            ui.add(
+                egui::ProgressBar::new(answered as f32 / total.max(1) as f32)
+                    .text(format!("{answered}/{total} answered, {correct} correct")),
+            );
+            ui.add_space(32.0);
+
+            let Some((sentence, prompt)) = game.session.current().cloned() else {
+                return;
+            };
+            let phase = game.session.phase();
+
+            // the sentence with the blank, whose words can be looked up unless they're english
+            ui.horizontal_wrapped(|ui| {
+                ui.spacing_mut().item_spacing.x = 6.0;
+
+                for word in prompt.first_half.split_whitespace() {
+                    word_label(ui, word, !inverse, &mut game.looked_up);
+                }
+
+                match phase {
+                    Phase::Prompting => {
+                        let width = 14.0 * prompt.word.chars().count().max(4) as f32;
+                        let hint = if inverse {
+                            String::from("?")
+                        } else {
+                            "_".repeat(prompt.word.chars().count())
+                        };
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut game.guess)
+                                .desired_width(width)
+                                .hint_text(hint),
+                        );
+                        // enter takes the focus away, which requesting it first would undo
+                        let submitted =
+                            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                        response.request_focus();
+
+                        if submitted {
+                            let guess = std::mem::take(&mut game.guess);
+                            if let Ok(feedback) = game.session.submit(&guess, game.lexicon.as_ref())
+                            {
+                                game.answers.push(Answer {
+                                    word: prompt.word.clone(),
+                                    guess,
+                                    feedback,
+                                });
+                            }
+                        }
+                    }
+                    _ => {
+                        let grade = game
+                            .answers
+                            .last()
+                            .map_or(Grade::Wrong, |a| a.feedback.grade);
+                        let text = RichText::new(prompt.word.trim())
+                            .color(colour(grade))
+                            .strong();
+                        if ui.link(text).clicked() && !inverse {
+                            game.looked_up = Some(prompt.word.trim().to_string());
+                        }
+                    }
+                }
+
+                for word in prompt.second_half.split_whitespace() {
+                    word_label(ui, word, !inverse, &mut game.looked_up);
+                }
+            });
+
+            ui.add_space(8.0);
+
+            // the other sentence, which is the one to look words up in in inverse mode
+            ui.horizontal_wrapped(|ui| {
+                let other = if inverse {
+                    sentence.get_translation().map_or("", |t| t.text.as_str())
+                } else {
+                    sentence.text.as_str()
+                };
+                for word in other.split_whitespace() {
+                    word_label(ui, word, inverse, &mut game.looked_up);
+                }
+            });
+
+            if phase != Phase::Answered {
+                return;
+            }
+
+            let Some(answer) = game.answers.last() else {
+                return;
+            };
+            let feedback = &answer.feedback;
+
+            ui.add_space(24.0);
+
+            let verdict = match feedback.grade {
+                Grade::Correct => "Correct",
+                Grade::Close => "Close",
+                Grade::Wrong => "Wrong",
+            };
+            ui.label(
+                RichText::new(format!("{verdict}, {}", answer.word.trim()))
+                    .color(colour(feedback.grade))
+                    .size(18.0),
+            );
+
+            if feedback.grade != Grade::Correct {
+                ui.label(format!("You wrote {}", answer.guess.trim()));
+            }
+            if let Some(reason) = feedback.describe() {
+                ui.label(format!("({})", render(&reason)));
+            }
+
+            ui.add_space(8.0);
+            advance = ui.button("Next").clicked() || ui.input(|i| i.key_pressed(egui::Key::Enter));
+        });
+
+        if !advance {
+            return Screen::Card(game);
+        }
+
+        game.looked_up = None;
+
+        match game.session.advance() {
+            Ok(Some(_)) => Screen::Results(game),
+            Ok(None) => Screen::Card(game),
+            Err(e) => {
+                self.error = Some(e);
+                Screen::Picker
+            }
+        }
+    }
+
+    fn results(&mut self, ctx: &egui::Context, mut game: Game) -> Screen {
+        let summary = game.session.summary();
+        let mut again = false;
+        let mut other = false;
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading(format!(
+                "{}/{} sentences correct",
+                summary.correct, summary.total
+            ));
+            if summary.rounds > 1 {
+                ui.label(format!(
+                    "{}/{} over {} rounds",
+                    summary.overall_correct, summary.overall_total, summary.rounds
+                ));
+            }
+            ui.add_space(16.0);
+
+            egui::Grid::new("answers").striped(true).show(ui, |ui| {
+                for answer in &game.answers {
+                    ui.label(
+                        RichText::new(answer.word.trim()).color(colour(answer.feedback.grade)),
+                    );
+                    if answer.feedback.grade == Grade::Correct {
+                        ui.label("");
+                    } else {
+                        ui.label(format!("you wrote {}", answer.guess.trim()));
+                    }
+                    ui.end_row();
+                }
+            });
+
+            ui.add_space(16.0);
+            ui.horizontal(|ui| {
+                again = ui.button("Play again").clicked();
+                other = ui.button("Choose another language").clicked();
+            });
+        });
+
+        if other {
+            return Screen::Picker;
+        }
+        if !again {
+            return Screen::Results(game);
+        }
+
+        if let Err(e) = game.session.replay() {
+            self.error = Some(e);
+            return Screen::Picker;
+        }
+        game.answers.clear();
+
+        // the dictionaries are already loaded, only the round needs fetching
+        let loader = self.loader;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(loader(&mut game.session).map(|_| game));
+        });
+
+        Screen::Loading(receiver)
+    }
+}
+
+impl Default for App {
+    fn default() -> App {
+        App::new()
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.ui(ctx);
+    }
+}
+
+// loads the session's next round, blocking the thread it's called on
+fn load(session: &mut GameSession) -> Result<usize, String> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let review = session.options().review;
+
+    match runtime.block_on(session.load_round())? {
+        0 if review => Err(String::from(
+            "Nothing to review yet: no cached sentences contain words you've missed",
+        )),
+        0 => Err(String::from("No sentences found for this language")),
+        len => Ok(len),
+    }
+}
+
+// a word of the sentence, which looks itself up when clicked if it's in the language being
+// learnt
+fn word_label(ui: &mut egui::Ui, word: &str, clickable: bool, looked_up: &mut Option<String>) {
+    if !clickable {
+        ui.label(word);
+    } else if ui.link(word).clicked() {
+        *looked_up = Some(remove_punctuation(word));
+    }
+}
+
+fn colour(grade: Grade) -> Color32 {
+    match grade {
+        Grade::Correct => CORRECT,
+        Grade::Close => CLOSE,
+        Grade::Wrong => WRONG,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use eframe::egui::accesskit::{Action, ActionRequest, Node, NodeId};
+    use eframe::egui::{Event, Key, Modifiers, Pos2, RawInput, Rect, Vec2};
+    use minicloze_lib::{
+        cache,
+        sentence::{Sentence, Translation},
+    };
+
+    use super::*;
+
+    // the app in a bare egui context, clicked through by accessibility actions the way a screen
+    // reader would, since there's no window to click in
+    struct Harness {
+        ctx: egui::Context,
+        app: App,
+        events: Vec<Event>,
+        nodes: Vec<(NodeId, Node)>,
+    }
+
+    impl Harness {
+        fn new(app: App) -> Harness {
+            let ctx = egui::Context::default();
+            ctx.enable_accesskit();
+
+            let mut harness = Harness {
+                ctx,
+                app,
+                events: Vec::new(),
+                nodes: Vec::new(),
+            };
+            harness.frame();
+            harness
+        }
+
+        fn frame(&mut self) {
+            let input = RawInput {
+                screen_rect: Some(Rect::from_min_size(Pos2::ZERO, Vec2::new(960.0, 640.0))),
+                events: std::mem::take(&mut self.events),
+                ..Default::default()
+            };
+
+            let output = self.ctx.run(input, |ctx| self.app.ui(ctx));
+            self.nodes = output
+                .platform_output
+                .accesskit_update
+                .map(|update| update.nodes)
+                .unwrap_or_default();
+        }
+
+        fn shows(&self, text: &str) -> bool {
+            self.nodes
+                .iter()
+                .any(|(_, node)| node.name().is_some_and(|name| name.contains(text)))
+        }
+
+        fn click(&mut self, name: &str) {
+            let (target, _) = self
+                .nodes
+                .iter()
+                .find(|(_, node)| node.name() == Some(name))
+                .unwrap_or_else(|| panic!("there's no {name} to click"));
+
+            self.events
+                .push(Event::AccessKitActionRequest(ActionRequest {
+                    action: Action::Default,
+                    target: *target,
+                    data: None,
+                }));
+            self.frame();
+            // a screen the click moved to is drawn from the frame after
+            self.frame();
+        }
+
+        // the guess box always has the focus
+        fn answer(&mut self, guess: &str) {
+            self.events.push(Event::Text(guess.to_string()));
+            self.events.push(Event::Key {
+                key: Key::Enter,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: Modifiers::NONE,
+            });
+            self.frame();
+            // the feedback is shown from the frame after
+            self.frame();
+        }
+
+        // runs frames until the round has been loaded on its thread
+        fn wait(&mut self) {
+            for _ in 0..500 {
+                if !matches!(self.app.screen, Screen::Loading(_)) {
+                    // the card's widgets are only there from the frame after
+                    self.frame();
+                    return;
+                }
+                thread::sleep(Duration::from_millis(10));
+                self.frame();
+            }
+            panic!("the round never loaded");
+        }
+
+        fn word(&self) -> String {
+            match &self.app.screen {
+                Screen::Card(game) => game.session.current().unwrap().1.word.clone(),
+                _ => panic!("there's no card"),
+            }
+        }
+    }
+
+    // loads from the cache instead of tatoeba
+    fn cached(session: &mut GameSession) -> Result<usize, String> {
+        let options = SessionOptions {
+            source: Source::Cache,
+            ..session.options().clone()
+        };
+        *session = GameSession::new(options, Some(1));
+        load(session)
+    }
+
+    fn sentence(id: i32, text: &str, translation: &str) -> Sentence {
+        Sentence {
+            id,
+            text: text.to_string(),
+            translations: vec![Translation {
+                id: id + 1000,
+                text: translation.to_string(),
+                transcriptions: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn plays_a_round() {
+        let dir = std::env::temp_dir().join(format!("minicloze-gui-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::env::set_var("XDG_DATA_HOME", &dir);
+        std::env::set_var("XDG_CONFIG_HOME", &dir);
+
+        let sentences = [sentence(1, "cat", "chat"), sentence(2, "dog", "chien")];
+        cache::store("fra", "eng", &sentences).unwrap();
+
+        let mut app = App::new();
+        app.loader = cached;
+        app.round_size = 2;
+        let mut harness = Harness::new(app);
+
+        let french = Language::from_code("fra").unwrap();
+        harness.click(&french.display_name());
+        harness.click("Play French");
+        harness.wait();
+
+        let word = harness.word();
+        harness.answer(&word);
+        assert!(harness.shows(&format!("Correct, {word}")));
+        harness.click("Next");
+
+        harness.answer("oiseau");
+        assert!(harness.shows("You wrote oiseau"));
+        harness.click("Next");
+
+        assert!(harness.shows("1/2 sentences correct"));
+        harness.click("Choose another language");
+        assert!(matches!(harness.app.screen, Screen::Picker));
+        assert!(harness.shows("What language do you want to study?"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
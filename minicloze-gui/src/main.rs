@@ -0,0 +1,20 @@
+// the desktop frontend. the game is minicloze_lib::session, this only shows it, see app.rs
+
+mod app;
+
+use eframe::egui;
+
+fn main() -> eframe::Result {
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title("minicloze")
+            .with_inner_size([960.0, 640.0]),
+        ..Default::default()
+    };
+
+    eframe::run_native(
+        "minicloze",
+        options,
+        Box::new(|_| Ok(Box::new(app::App::new()))),
+    )
+}
@@ -0,0 +1,86 @@
+// generates the language registry from src/langs.txt, see src/langs.rs. each line of langs.txt
+// is "code<TAB>name<TAB>metadata", most spoken on tatoeba first. the metadata is a "; "
+// separated list of iso1=<ISO 639-1 code>, script=<ISO 15924 code, Latn if left out>,
+// unspaced, wiktionary=<section name, if it isn't the name without any bracketed part> and
+// endonym=<the language's name for itself>
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// scripts written right to left
+const RTL_SCRIPTS: [&str; 9] = [
+    "Arab", "Hebr", "Syrc", "Thaa", "Nkoo", "Phnx", "Armi", "Phli", "Orkh",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/langs.txt");
+
+    let langs = fs::read_to_string("src/langs.txt").expect("src/langs.txt is missing");
+
+    let mut languages = String::new();
+    let mut codes = phf_codegen::Map::new();
+    let mut names = phf_codegen::Map::new();
+    let mut iso1_codes = phf_codegen::Map::new();
+
+    for (index, line) in langs.lines().filter(|l| !l.trim().is_empty()).enumerate() {
+        let mut columns = line.split('\t');
+        let code = columns.next().unwrap().trim();
+        let name = columns
+            .next()
+            .unwrap_or_else(|| panic!("{code} has no name"))
+            .trim();
+        let metadata: Vec<&str> = columns
+            .next()
+            .unwrap_or_default()
+            .split(';')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .collect();
+
+        let value = |key: &str| {
+            metadata
+                .iter()
+                .find_map(|m| m.strip_prefix(key)?.strip_prefix('='))
+        };
+
+        let iso1 = value("iso1");
+        let script = value("script").unwrap_or("Latn");
+        let direction = if RTL_SCRIPTS.contains(&script) {
+            "Rtl"
+        } else {
+            "Ltr"
+        };
+        let spaced = !metadata.contains(&"unspaced");
+        // "Central Kurdish (Soranî)" -> "Central Kurdish"
+        let wiktionary = value("wiktionary").unwrap_or(name.split(" (").next().unwrap());
+        let endonym = value("endonym");
+
+        languages.push_str(&format!(
+            "    Language {{ code: {code:?}, name: {name:?}, rank: {}, iso1: {iso1:?}, \
+             script: {script:?}, direction: Direction::{direction}, spaced: {spaced}, \
+             wiktionary: {wiktionary:?}, endonym: {endonym:?} }},\n",
+            index + 1
+        ));
+
+        codes.entry(code.to_string(), &index.to_string());
+        names.entry(name.to_string(), &index.to_string());
+        if let Some(iso1) = iso1 {
+            iso1_codes.entry(iso1.to_string(), &index.to_string());
+        }
+    }
+
+    let count = langs.lines().filter(|l| !l.trim().is_empty()).count();
+    let generated = format!(
+        "static LANGUAGES: [Language; {count}] = [\n{languages}];\n\n\
+         static CODES: phf::Map<&'static str, usize> = {};\n\n\
+         static NAMES: phf::Map<&'static str, usize> = {};\n\n\
+         static ISO1_CODES: phf::Map<&'static str, usize> = {};\n",
+        codes.build(),
+        names.build(),
+        iso1_codes.build()
+    );
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("langs.rs");
+    fs::write(out, generated).expect("couldn't write the language registry");
+}
@@ -0,0 +1,179 @@
+pick-language = Welche Sprache möchtest du lernen?
+fetching = Sätze werden geladen...
+fetched = Fertig in { $time }, { $count } Sätze verarbeitet.
+fetch-failed = Sätze konnten nicht geladen werden: { $error }
+no-sentences = Keine Sätze für diese Sprache gefunden
+nothing-to-review = Nichts zu wiederholen: Du hattest noch keinen Satz falsch, oder keiner passt zu den Filtern
+
+correct = Richtig, { $answer }
+close = Fast, { $answer }.
+wrong = Falsch, { $answer }.
+leech = Dieses Wort vergisst du immer wieder. Eine Eselsbrücke hilft vielleicht mehr als noch eine Wiederholung.
+no-dictionaries = Keine Offline-Wörterbücher für diese Sprache.
+
+round-score = { $correct }/{ $total } Sätze richtig. Nochmal spielen?
+overall-score = { $correct }/{ $total } Sätze in dieser Runde richtig, { $overall_correct }/{ $overall_total } insgesamt. Nochmal spielen?
+yes = Ja
+no = Nein
+yes-no = (j/n)
+
+blank = (Lücke)
+blank-letters = { $count ->
+    [one] (Lücke, { $count } Buchstabe)
+   *[other] (Lücke, { $count } Buchstaben)
+}
+letter-wrong = Buchstabe { $position } ist { $expected }, nicht { $guessed }
+letter-missing = Buchstabe { $position }, { $expected }, fehlt
+letter-extra = { $guessed } nach Buchstabe { $position } ist zu viel
+
+mistake-case-only = falsche Groß- und Kleinschreibung
+mistake-accent-missing = achte auf die Akzente
+mistake-transposition = zwei Buchstaben vertauscht
+mistake-wrong-inflection = falsche Form
+mistake-wrong-ending = falsche Endung
+mistake-different-word = anderes Wort
+inflection-spelling = richtiges { $part_of_speech }, falsche Schreibung: { $expected } erwartet
+inflection-wrong = richtiges { $part_of_speech }, falsch bei { $wrong }: { $expected } erwartet
+and = und
+part-of-speech-verb = Verb
+part-of-speech-noun = Nomen
+part-of-speech-adjective = Adjektiv
+part-of-speech-adverb = Adverb
+part-of-speech-pronoun = Pronomen
+part-of-speech-determiner = Determinativ
+part-of-speech-word = Wort
+dimension-form = Form
+dimension-person = Person
+dimension-number = Numerus
+dimension-tense = Tempus
+dimension-aspect = Aspekt
+dimension-mood = Modus
+dimension-gender = Genus
+dimension-case = Kasus
+dimension-definiteness = Definitheit
+feature-present = Präsens
+feature-past = Präteritum
+feature-future = Futur
+feature-imperfective = imperfektiv
+feature-perfective = perfektiv
+feature-perfect = Perfekt
+feature-subjunctive = Konjunktiv
+feature-conditional = Konditional
+feature-imperative = Imperativ
+feature-infinitive = Infinitiv
+feature-participle = Partizip
+feature-converb = Konverb
+feature-masculine = maskulin
+feature-feminine = feminin
+feature-neuter = neutrum
+feature-definite = definit
+feature-indefinite = indefinit
+
+tui-progress = { $answered }/{ $total } beantwortet, { $correct } richtig
+tui-history = Diese Runde
+tui-dictionary = Wörterbuch
+tui-no-definitions = Keine Definitionen
+tui-guess = Du hast { $guess } geschrieben
+tui-replay = Enter spielt nochmal, Esc beendet
+tui-help = Enter antwortet · ↑↓ frühere Sätze · ←→ Wort wählen · Esc beendet
+tui-plain = Die Vollbildoberfläche kann nicht mit einfacher Ausgabe verwendet werden
+
+langs-hint = `minicloze langs` listet alle Sprachen auf
+one-file = Es kann nur eine Datei auf einmal importiert werden
+not-a-file = { $path } ist keine Datei
+copy-failed = { $path } konnte nicht kopiert werden: { $error }
+read-failed = { $path } konnte nicht gelesen werden: { $error }
+imported-forms = { $count } Formen importiert.
+imported-definitions = Definitionen für { $count } Wörter importiert.
+imported-dictionaries = Für { $language } sind jetzt { $count } Wörterbücher verfügbar.
+imported-pairs = { $count } Satzpaare importiert.
+imported-cache = { $count } Sätze zum Cache hinzugefügt.
+imported-vocabulary = { $count } Wörter zu deinen bekannten Wörtern in { $language } hinzugefügt.
+backed-up = { $count } Antworten in { $path } gesichert.
+restored = { $count } Antworten wiederhergestellt, der ersetzte Verlauf liegt in answers.jsonl.bak.
+
+cache-fetched = { $count } Sätze geladen, davon { $new } neu.
+cache-from = (aus { $native })
+cache-sentences = { $count } Sätze
+cache-cleared = { $count } Cache-Dateien entfernt.
+
+srs-status = { $language }: { $cards } Karten, { $due } jetzt fällig
+srs-leeches = Blutegel, { $lapses }-mal oder öfter vergessen:
+srs-leech = (Satz { $sentence }, { $lapses }-mal vergessen)
+srs-fitted = FSRS an { $answers } Antworten zu { $cards } Sätzen angepasst.
+srs-log-loss = Log-Loss: { $before } -> { $after } ({ $better } % besser)
+srs-retention = Vorhergesagte Behaltensrate: { $before } % -> { $after } %, tatsächlich { $actual } %
+srs-unchanged = Deine bisherigen Parameter passen am besten, sie bleiben unverändert.
+srs-saved = Die Parameter für { $language } wurden gespeichert und gelten ab dem nächsten Spiel.
+
+stats-score = { $language }: { $correct }/{ $total } richtig ({ $percent } %), { $words } verschiedene Wörter
+stats-accuracy = Genauigkeit, letzte { $weeks } Wochen: { $line }
+stats-words = Wörter: { $seen } gesehen, { $learned } gelernt, { $mastered } gemeistert
+stats-answer-time = durchschnittliche Antwortzeit: { $seconds } s
+stats-missed-words = am häufigsten verfehlte Wörter: { $list }
+stats-missed-lemmas = am häufigsten verfehlte Lemmata: { $list }
+stats-nothing-played = Noch nichts gespielt.
+stats-nothing-answered = nichts beantwortet
+stats-streak = Serie:
+stats-streak-days = { $current } Tage, längste { $longest }
+stats-active-days = An { $active } der letzten { $days } Tage geantwortet.
+stats-activity = Aktivität, letzte { $weeks } Wochen:
+stats-monday = Mo
+stats-wednesday = Mi
+stats-friday = Fr
+stats-sunday = So
+
+config-file = Konfigurationsdatei: { $path }
+config-data = Datenverzeichnis:    { $path }
+config-no-data = keins gefunden
+
+editor-settings = Einstellungen
+editor-language = Standardsprache: { $value }
+editor-native = Muttersprache: { $value }
+editor-direction = Richtung: { $value }
+editor-round-size = Rundengröße: { $value }
+editor-strategy = Lückenwahl: { $value }
+editor-theme = Farbschema: { $value }
+editor-plain = Einfache Ausgabe: { $value }
+editor-dictionary = Wörterbuch: { $value }
+editor-furigana = Furigana: { $value }
+editor-close-distance = Knapp, wenn weniger als { $value } Änderungen entfernt
+editor-case-sensitive = Groß- und Kleinschreibung zählt: { $value }
+editor-accent-sensitive = Akzente zählen: { $value }
+editor-scheduler = Wiederholungen: { $value }
+editor-overrides = Einstellungen pro Sprache ({ $count })
+editor-save = Speichern und beenden
+editor-quit = Beenden ohne zu speichern
+editor-saved = In { $path } gespeichert.
+editor-remove = Für alles die Standardwerte verwenden
+editor-done = Fertig
+editor-ask = fragen
+editor-default = Standard
+editor-normal = normal
+editor-inverse = umgekehrt
+editor-random = zufällig
+editor-longest = längstes
+editor-off = aus
+
+editor-ask-language = Standardmäßig gespielte Sprache
+editor-ask-native = Sprache der Übersetzungen
+editor-ask-round-size = Sätze pro Runde
+editor-ask-theme = Farbschema
+editor-ask-plain = Screenreader-freundliche Ausgabe, ohne Farben, Links oder Menüs
+editor-ask-furigana = Furigana nach dem Antworten zeigen
+editor-ask-close-distance = Knapp, wenn weniger als so viele Änderungen entfernt
+editor-ask-case-sensitive = Falsche Groß- und Kleinschreibung zählen
+editor-ask-accent-sensitive = Falsche Akzente zählen
+editor-ask-scheduler = Wie Wiederholungen geplant werden
+editor-ask-direction = Richtung
+editor-ask-strategy = Welches Wort ausgelassen wird
+editor-ask-dictionary = Wohin Wörter verlinken
+editor-dictionary-help = wiktionary, wiktionary:<Ausgabe>, jisho oder eine URL mit { "{word}" }
+editor-unknown-dictionary = Dieses Wörterbuch kennt minicloze nicht
+editor-at-least-one = Es muss mindestens 1 sein
+editor-which-language = Welche Sprache?
+editor-overrides-for = Einstellungen für { $language }
+editor-override-native = Muttersprache
+editor-override = Überschreiben
+editor-change = Ändern
+editor-use-default = Standard verwenden
@@ -0,0 +1,188 @@
+# the interface in english, which is also used for anything another catalog is missing.
+# $answer is the blanked word, $count and $position are numbers
+
+pick-language = What language do you want to study?
+fetching = Fetching sentences for you...
+fetched = Processing complete in { $time }, { $count } sentences parsed.
+fetch-failed = Couldn't fetch sentences: { $error }
+no-sentences = No sentences found for this language
+nothing-to-review = Nothing to review: you haven't missed any sentences yet, or none you have match the filters
+
+correct = Correct, { $answer }
+close = Close, { $answer }.
+wrong = Wrong, { $answer }.
+leech = You keep forgetting this one, it's a leech. A mnemonic might help more than another review.
+no-dictionaries = No offline dictionaries for this language.
+
+round-score = { $correct }/{ $total } sentences correct. Play again?
+overall-score = { $correct }/{ $total } sentences correct locally, { $overall_correct }/{ $overall_total } sentences correct overall. Play again?
+yes = Yes
+no = No
+yes-no = (y/n)
+
+# read out by screen readers in plain mode
+blank = (blank)
+blank-letters = { $count ->
+    [one] (blank, { $count } letter)
+   *[other] (blank, { $count } letters)
+}
+letter-wrong = letter { $position } is { $expected }, not { $guessed }
+letter-missing = letter { $position }, { $expected }, is missing
+letter-extra = { $guessed } after letter { $position } shouldn't be there
+
+# what went wrong with an answer. $part_of_speech, $wrong and $expected are the messages below
+mistake-case-only = wrong capitalisation
+mistake-accent-missing = check the accents
+mistake-transposition = two letters swapped
+mistake-wrong-inflection = wrong form
+mistake-wrong-ending = wrong ending
+mistake-different-word = different word
+inflection-spelling = right { $part_of_speech }, wrong spelling: { $expected } expected
+inflection-wrong = right { $part_of_speech }, wrong { $wrong }: { $expected } expected
+and = and
+part-of-speech-verb = verb
+part-of-speech-noun = noun
+part-of-speech-adjective = adjective
+part-of-speech-adverb = adverb
+part-of-speech-pronoun = pronoun
+part-of-speech-determiner = determiner
+part-of-speech-word = word
+dimension-form = form
+dimension-person = person
+dimension-number = number
+dimension-tense = tense
+dimension-aspect = aspect
+dimension-mood = mood
+dimension-gender = gender
+dimension-case = case
+dimension-definiteness = definiteness
+feature-present = present
+feature-past = past
+feature-future = future
+feature-imperfective = imperfective
+feature-perfective = perfective
+feature-perfect = perfect
+feature-subjunctive = subjunctive
+feature-conditional = conditional
+feature-imperative = imperative
+feature-infinitive = infinitive
+feature-participle = participle
+feature-converb = converb
+feature-masculine = masculine
+feature-feminine = feminine
+feature-neuter = neuter
+feature-definite = definite
+feature-indefinite = indefinite
+
+# the full-screen interface
+tui-progress = { $answered }/{ $total } answered, { $correct } correct
+tui-history = This round
+tui-dictionary = Dictionary
+tui-no-definitions = No definitions
+tui-guess = You wrote { $guess }
+tui-replay = Enter plays again, Esc quits
+tui-help = Enter answers · ↑↓ earlier prompts · ←→ pick a word · Esc quits
+tui-plain = The full-screen interface can't be used with plain output
+
+# the other subcommands. $path is a file, $language a language's name
+langs-hint = `minicloze langs` lists every language
+one-file = Only one file can be imported at a time
+not-a-file = { $path } isn't a file
+copy-failed = Couldn't copy { $path }: { $error }
+read-failed = Couldn't read { $path }: { $error }
+imported-forms = Imported { $count } forms.
+imported-definitions = Imported definitions for { $count } words.
+imported-dictionaries = { $count } dictionaries are now available for { $language }.
+imported-pairs = Imported { $count } sentence pairs.
+imported-cache = Added { $count } sentences to the cache.
+imported-vocabulary = Added { $count } words to those you know in { $language }.
+backed-up = Backed up { $count } answers to { $path }.
+restored = Restored { $count } answers, the history they replaced is in answers.jsonl.bak.
+
+cache-fetched = Fetched { $count } sentences, { $new } of them new.
+cache-from = (from { $native })
+cache-sentences = { $count } sentences
+cache-cleared = Removed { $count } cache files.
+
+srs-status = { $language }: { $cards } cards, { $due } due now
+srs-leeches = Leeches, forgotten { $lapses } times or more:
+srs-leech = (sentence { $sentence }, { $lapses } lapses)
+srs-fitted = Fitted FSRS to { $answers } answers for { $cards } sentences.
+srs-log-loss = Log loss: { $before } -> { $after } ({ $better }% better)
+srs-retention = Predicted retention: { $before }% -> { $after }%, actual { $actual }%
+srs-unchanged = The parameters you have already fit best, so they're unchanged.
+srs-saved = Saved the parameters for { $language }, they're used from the next game.
+
+stats-score = { $language }: { $correct }/{ $total } correct ({ $percent }%), { $words } different words
+stats-accuracy = accuracy, last { $weeks } weeks: { $line }
+stats-words = words: { $seen } seen, { $learned } learned, { $mastered } mastered
+stats-answer-time = average answer time: { $seconds }s
+stats-missed-words = most missed words: { $list }
+stats-missed-lemmas = most missed lemmas: { $list }
+stats-nothing-played = Nothing played yet.
+stats-nothing-answered = nothing answered
+stats-streak = Streak:
+stats-streak-days = { $current } days, longest { $longest }
+stats-active-days = Answered on { $active } of the last { $days } days.
+stats-activity = Activity, last { $weeks } weeks:
+# the heatmap's rows, only every other day is labelled
+stats-monday = Mon
+stats-wednesday = Wed
+stats-friday = Fri
+stats-sunday = Sun
+
+config-file = config file:    { $path }
+config-data = data directory: { $path }
+config-no-data = none found
+
+# the config editor. $value is a setting's current value
+editor-settings = Settings
+editor-language = Default language: { $value }
+editor-native = Native language: { $value }
+editor-direction = Direction: { $value }
+editor-round-size = Round size: { $value }
+editor-strategy = Blank choice: { $value }
+editor-theme = Theme: { $value }
+editor-plain = Plain output: { $value }
+editor-dictionary = Dictionary: { $value }
+editor-furigana = Furigana: { $value }
+editor-close-distance = Close if fewer than { $value } edits away
+editor-case-sensitive = Capitalisation counts: { $value }
+editor-accent-sensitive = Accents count: { $value }
+editor-scheduler = Reviews: { $value }
+editor-overrides = Per-language settings ({ $count })
+editor-save = Save and quit
+editor-quit = Quit without saving
+editor-saved = Saved to { $path }.
+editor-remove = Use the defaults for everything
+editor-done = Done
+editor-ask = ask
+editor-default = default
+editor-normal = normal
+editor-inverse = inverse
+editor-random = random
+editor-longest = longest
+editor-off = off
+
+editor-ask-language = Language to play by default
+editor-ask-native = Language translations are shown in
+editor-ask-round-size = Sentences per round
+editor-ask-theme = Colour theme
+editor-ask-plain = Screen-reader friendly output, without colours, links or menus
+editor-ask-furigana = Show furigana after answering
+editor-ask-close-distance = Close if fewer than this many edits away
+editor-ask-case-sensitive = Count wrong capitalisation
+editor-ask-accent-sensitive = Count wrong accents
+editor-ask-scheduler = How reviews are scheduled
+editor-ask-direction = Direction
+editor-ask-strategy = Which word to blank
+editor-ask-dictionary = Where words link to
+editor-dictionary-help = wiktionary, wiktionary:<edition>, jisho, or a URL with { "{word}" }
+editor-unknown-dictionary = That isn't a dictionary minicloze knows
+editor-at-least-one = It has to be at least 1
+editor-which-language = Which language?
+editor-overrides-for = Settings for { $language }
+editor-override-native = Native language
+editor-override = Override
+editor-change = Change it
+editor-use-default = Use the default
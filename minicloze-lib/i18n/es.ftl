@@ -0,0 +1,179 @@
+pick-language = ¿Qué idioma quieres estudiar?
+fetching = Buscando frases...
+fetched = Listo en { $time }, { $count } frases procesadas.
+fetch-failed = No se pudieron obtener frases: { $error }
+no-sentences = No se encontraron frases para este idioma
+nothing-to-review = No hay nada que repasar: todavía no has fallado ninguna frase, o ninguna coincide con los filtros
+
+correct = Correcto, { $answer }
+close = Casi, { $answer }.
+wrong = Incorrecto, { $answer }.
+leech = Esta palabra se te olvida una y otra vez. Quizá te ayude más una regla mnemotécnica que otro repaso.
+no-dictionaries = No hay diccionarios sin conexión para este idioma.
+
+round-score = { $correct }/{ $total } frases correctas. ¿Jugar otra vez?
+overall-score = { $correct }/{ $total } frases correctas en esta ronda, { $overall_correct }/{ $overall_total } en total. ¿Jugar otra vez?
+yes = Sí
+no = No
+yes-no = (s/n)
+
+blank = (hueco)
+blank-letters = { $count ->
+    [one] (hueco, { $count } letra)
+   *[other] (hueco, { $count } letras)
+}
+letter-wrong = la letra { $position } es { $expected }, no { $guessed }
+letter-missing = falta la letra { $position }, { $expected }
+letter-extra = sobra { $guessed } después de la letra { $position }
+
+mistake-case-only = mayúsculas incorrectas
+mistake-accent-missing = revisa las tildes
+mistake-transposition = dos letras intercambiadas
+mistake-wrong-inflection = forma incorrecta
+mistake-wrong-ending = terminación incorrecta
+mistake-different-word = otra palabra
+inflection-spelling = categoría correcta ({ $part_of_speech }), ortografía incorrecta: se esperaba { $expected }
+inflection-wrong = categoría correcta ({ $part_of_speech }), pero falla { $wrong }: se esperaba { $expected }
+and = y
+part-of-speech-verb = verbo
+part-of-speech-noun = sustantivo
+part-of-speech-adjective = adjetivo
+part-of-speech-adverb = adverbio
+part-of-speech-pronoun = pronombre
+part-of-speech-determiner = determinante
+part-of-speech-word = palabra
+dimension-form = forma
+dimension-person = persona
+dimension-number = número
+dimension-tense = tiempo
+dimension-aspect = aspecto
+dimension-mood = modo
+dimension-gender = género
+dimension-case = caso
+dimension-definiteness = definitud
+feature-present = presente
+feature-past = pasado
+feature-future = futuro
+feature-imperfective = imperfectivo
+feature-perfective = perfectivo
+feature-perfect = perfecto
+feature-subjunctive = subjuntivo
+feature-conditional = condicional
+feature-imperative = imperativo
+feature-infinitive = infinitivo
+feature-participle = participio
+feature-converb = gerundio
+feature-masculine = masculino
+feature-feminine = femenino
+feature-neuter = neutro
+feature-definite = definido
+feature-indefinite = indefinido
+
+tui-progress = { $answered }/{ $total } respondidas, { $correct } correctas
+tui-history = Esta ronda
+tui-dictionary = Diccionario
+tui-no-definitions = Sin definiciones
+tui-guess = Escribiste { $guess }
+tui-replay = Intro para jugar otra vez, Esc para salir
+tui-help = Intro responde · ↑↓ frases anteriores · ←→ elegir una palabra · Esc sale
+tui-plain = La interfaz a pantalla completa no se puede usar con la salida simple
+
+langs-hint = `minicloze langs` muestra todos los idiomas
+one-file = Solo se puede importar un archivo a la vez
+not-a-file = { $path } no es un archivo
+copy-failed = No se pudo copiar { $path }: { $error }
+read-failed = No se pudo leer { $path }: { $error }
+imported-forms = { $count } formas importadas.
+imported-definitions = Definiciones importadas para { $count } palabras.
+imported-dictionaries = Ahora hay { $count } diccionarios disponibles para { $language }.
+imported-pairs = { $count } pares de frases importados.
+imported-cache = { $count } frases añadidas a la caché.
+imported-vocabulary = { $count } palabras añadidas a las que conoces en { $language }.
+backed-up = { $count } respuestas guardadas en { $path }.
+restored = { $count } respuestas restauradas, el historial que reemplazaron está en answers.jsonl.bak.
+
+cache-fetched = { $count } frases obtenidas, { $new } de ellas nuevas.
+cache-from = (desde { $native })
+cache-sentences = { $count } frases
+cache-cleared = { $count } archivos de caché eliminados.
+
+srs-status = { $language }: { $cards } tarjetas, { $due } pendientes ahora
+srs-leeches = Sanguijuelas, olvidadas { $lapses } veces o más:
+srs-leech = (frase { $sentence }, { $lapses } olvidos)
+srs-fitted = FSRS ajustado a { $answers } respuestas de { $cards } frases.
+srs-log-loss = Pérdida logarítmica: { $before } -> { $after } ({ $better } % mejor)
+srs-retention = Retención prevista: { $before } % -> { $after } %, real { $actual } %
+srs-unchanged = Los parámetros que ya tienes son los que mejor se ajustan, así que no cambian.
+srs-saved = Parámetros guardados para { $language }, se usan a partir de la próxima partida.
+
+stats-score = { $language }: { $correct }/{ $total } correctas ({ $percent } %), { $words } palabras distintas
+stats-accuracy = precisión, últimas { $weeks } semanas: { $line }
+stats-words = palabras: { $seen } vistas, { $learned } aprendidas, { $mastered } dominadas
+stats-answer-time = tiempo medio de respuesta: { $seconds } s
+stats-missed-words = palabras más falladas: { $list }
+stats-missed-lemmas = lemas más fallados: { $list }
+stats-nothing-played = Todavía no has jugado.
+stats-nothing-answered = nada respondido
+stats-streak = Racha:
+stats-streak-days = { $current } días, la más larga { $longest }
+stats-active-days = Respondiste { $active } de los últimos { $days } días.
+stats-activity = Actividad, últimas { $weeks } semanas:
+stats-monday = lun
+stats-wednesday = mié
+stats-friday = vie
+stats-sunday = dom
+
+config-file = archivo de configuración: { $path }
+config-data = directorio de datos:      { $path }
+config-no-data = no se encontró ninguno
+
+editor-settings = Ajustes
+editor-language = Idioma predeterminado: { $value }
+editor-native = Idioma nativo: { $value }
+editor-direction = Dirección: { $value }
+editor-round-size = Tamaño de la ronda: { $value }
+editor-strategy = Elección del hueco: { $value }
+editor-theme = Tema: { $value }
+editor-plain = Salida simple: { $value }
+editor-dictionary = Diccionario: { $value }
+editor-furigana = Furigana: { $value }
+editor-close-distance = Casi, si está a menos de { $value } ediciones
+editor-case-sensitive = Las mayúsculas cuentan: { $value }
+editor-accent-sensitive = Los acentos cuentan: { $value }
+editor-scheduler = Repasos: { $value }
+editor-overrides = Ajustes por idioma ({ $count })
+editor-save = Guardar y salir
+editor-quit = Salir sin guardar
+editor-saved = Guardado en { $path }.
+editor-remove = Usar los valores predeterminados para todo
+editor-done = Listo
+editor-ask = preguntar
+editor-default = predeterminado
+editor-normal = normal
+editor-inverse = inversa
+editor-random = al azar
+editor-longest = la más larga
+editor-off = desactivado
+
+editor-ask-language = Idioma para jugar por defecto
+editor-ask-native = Idioma en que se muestran las traducciones
+editor-ask-round-size = Frases por ronda
+editor-ask-theme = Tema de colores
+editor-ask-plain = Salida adaptada a lectores de pantalla, sin colores, enlaces ni menús
+editor-ask-furigana = Mostrar furigana después de responder
+editor-ask-close-distance = Casi, si está a menos de tantas ediciones
+editor-ask-case-sensitive = Contar las mayúsculas incorrectas
+editor-ask-accent-sensitive = Contar los acentos incorrectos
+editor-ask-scheduler = Cómo se programan los repasos
+editor-ask-direction = Dirección
+editor-ask-strategy = Qué palabra ocultar
+editor-ask-dictionary = Adónde enlazan las palabras
+editor-dictionary-help = wiktionary, wiktionary:<edición>, jisho, o una URL con { "{word}" }
+editor-unknown-dictionary = minicloze no conoce ese diccionario
+editor-at-least-one = Tiene que ser al menos 1
+editor-which-language = ¿Qué idioma?
+editor-overrides-for = Ajustes para { $language }
+editor-override-native = Idioma nativo
+editor-override = Cambiar el valor predeterminado
+editor-change = Cambiarlo
+editor-use-default = Usar el predeterminado
@@ -0,0 +1,179 @@
+pick-language = Quelle langue voulez-vous étudier ?
+fetching = Recherche de phrases...
+fetched = Terminé en { $time }, { $count } phrases analysées.
+fetch-failed = Impossible de récupérer des phrases : { $error }
+no-sentences = Aucune phrase trouvée pour cette langue
+nothing-to-review = Rien à réviser : vous n'avez encore manqué aucune phrase, ou aucune ne correspond aux filtres
+
+correct = Correct, { $answer }
+close = Presque, { $answer }.
+wrong = Faux, { $answer }.
+leech = Ce mot vous échappe sans cesse. Un moyen mnémotechnique vous aidera peut-être plus qu'une nouvelle révision.
+no-dictionaries = Aucun dictionnaire hors ligne pour cette langue.
+
+round-score = { $correct }/{ $total } phrases correctes. Rejouer ?
+overall-score = { $correct }/{ $total } phrases correctes pour cette partie, { $overall_correct }/{ $overall_total } au total. Rejouer ?
+yes = Oui
+no = Non
+yes-no = (o/n)
+
+blank = (blanc)
+blank-letters = { $count ->
+    [one] (blanc, { $count } lettre)
+   *[other] (blanc, { $count } lettres)
+}
+letter-wrong = la lettre { $position } est { $expected }, pas { $guessed }
+letter-missing = la lettre { $position }, { $expected }, manque
+letter-extra = { $guessed } après la lettre { $position } est en trop
+
+mistake-case-only = majuscules incorrectes
+mistake-accent-missing = vérifiez les accents
+mistake-transposition = deux lettres inversées
+mistake-wrong-inflection = mauvaise forme
+mistake-wrong-ending = mauvaise terminaison
+mistake-different-word = autre mot
+inflection-spelling = bonne catégorie ({ $part_of_speech }), mauvaise orthographe : { $expected } attendu
+inflection-wrong = bonne catégorie ({ $part_of_speech }), erreur de { $wrong } : { $expected } attendu
+and = et
+part-of-speech-verb = verbe
+part-of-speech-noun = nom
+part-of-speech-adjective = adjectif
+part-of-speech-adverb = adverbe
+part-of-speech-pronoun = pronom
+part-of-speech-determiner = déterminant
+part-of-speech-word = mot
+dimension-form = forme
+dimension-person = personne
+dimension-number = nombre
+dimension-tense = temps
+dimension-aspect = aspect
+dimension-mood = mode
+dimension-gender = genre
+dimension-case = cas
+dimension-definiteness = définitude
+feature-present = présent
+feature-past = passé
+feature-future = futur
+feature-imperfective = imperfectif
+feature-perfective = perfectif
+feature-perfect = parfait
+feature-subjunctive = subjonctif
+feature-conditional = conditionnel
+feature-imperative = impératif
+feature-infinitive = infinitif
+feature-participle = participe
+feature-converb = converbe
+feature-masculine = masculin
+feature-feminine = féminin
+feature-neuter = neutre
+feature-definite = défini
+feature-indefinite = indéfini
+
+tui-progress = { $answered }/{ $total } répondues, { $correct } correctes
+tui-history = Cette partie
+tui-dictionary = Dictionnaire
+tui-no-definitions = Aucune définition
+tui-guess = Vous avez écrit { $guess }
+tui-replay = Entrée pour rejouer, Échap pour quitter
+tui-help = Entrée répond · ↑↓ phrases précédentes · ←→ choisir un mot · Échap quitte
+tui-plain = L'interface plein écran ne peut pas être utilisée en mode simple
+
+langs-hint = `minicloze langs` liste toutes les langues
+one-file = Un seul fichier peut être importé à la fois
+not-a-file = { $path } n'est pas un fichier
+copy-failed = Impossible de copier { $path } : { $error }
+read-failed = Impossible de lire { $path } : { $error }
+imported-forms = { $count } formes importées.
+imported-definitions = Définitions importées pour { $count } mots.
+imported-dictionaries = { $count } dictionnaires sont maintenant disponibles pour { $language }.
+imported-pairs = { $count } paires de phrases importées.
+imported-cache = { $count } phrases ajoutées au cache.
+imported-vocabulary = { $count } mots ajoutés à ceux que vous connaissez en { $language }.
+backed-up = { $count } réponses sauvegardées dans { $path }.
+restored = { $count } réponses restaurées, l'historique remplacé est dans answers.jsonl.bak.
+
+cache-fetched = { $count } phrases récupérées, dont { $new } nouvelles.
+cache-from = (depuis { $native })
+cache-sentences = { $count } phrases
+cache-cleared = { $count } fichiers de cache supprimés.
+
+srs-status = { $language } : { $cards } cartes, { $due } à réviser maintenant
+srs-leeches = Sangsues, oubliées { $lapses } fois ou plus :
+srs-leech = (phrase { $sentence }, { $lapses } oublis)
+srs-fitted = FSRS ajusté sur { $answers } réponses pour { $cards } phrases.
+srs-log-loss = Perte logarithmique : { $before } -> { $after } ({ $better } % de mieux)
+srs-retention = Rétention prévue : { $before } % -> { $after } %, réelle { $actual } %
+srs-unchanged = Les paramètres que vous avez déjà sont les meilleurs, ils ne changent donc pas.
+srs-saved = Paramètres enregistrés pour { $language }, ils seront utilisés dès la prochaine partie.
+
+stats-score = { $language } : { $correct }/{ $total } correctes ({ $percent } %), { $words } mots différents
+stats-accuracy = précision, { $weeks } dernières semaines : { $line }
+stats-words = mots : { $seen } vus, { $learned } appris, { $mastered } maîtrisés
+stats-answer-time = temps de réponse moyen : { $seconds } s
+stats-missed-words = mots les plus manqués : { $list }
+stats-missed-lemmas = lemmes les plus manqués : { $list }
+stats-nothing-played = Aucune partie jouée pour l'instant.
+stats-nothing-answered = aucune réponse
+stats-streak = Série :
+stats-streak-days = { $current } jours, la plus longue { $longest }
+stats-active-days = Réponses données { $active } des { $days } derniers jours.
+stats-activity = Activité, { $weeks } dernières semaines :
+stats-monday = lun
+stats-wednesday = mer
+stats-friday = ven
+stats-sunday = dim
+
+config-file = fichier de configuration : { $path }
+config-data = dossier des données :     { $path }
+config-no-data = aucun trouvé
+
+editor-settings = Réglages
+editor-language = Langue par défaut : { $value }
+editor-native = Langue maternelle : { $value }
+editor-direction = Sens : { $value }
+editor-round-size = Taille de la partie : { $value }
+editor-strategy = Choix du blanc : { $value }
+editor-theme = Thème : { $value }
+editor-plain = Affichage simple : { $value }
+editor-dictionary = Dictionnaire : { $value }
+editor-furigana = Furigana : { $value }
+editor-close-distance = Presque, à moins de { $value } modifications
+editor-case-sensitive = Les majuscules comptent : { $value }
+editor-accent-sensitive = Les accents comptent : { $value }
+editor-scheduler = Révisions : { $value }
+editor-overrides = Réglages par langue ({ $count })
+editor-save = Enregistrer et quitter
+editor-quit = Quitter sans enregistrer
+editor-saved = Enregistré dans { $path }.
+editor-remove = Utiliser les valeurs par défaut pour tout
+editor-done = Terminé
+editor-ask = demander
+editor-default = par défaut
+editor-normal = normal
+editor-inverse = inverse
+editor-random = au hasard
+editor-longest = le plus long
+editor-off = désactivées
+
+editor-ask-language = Langue jouée par défaut
+editor-ask-native = Langue des traductions
+editor-ask-round-size = Phrases par partie
+editor-ask-theme = Thème de couleurs
+editor-ask-plain = Affichage adapté aux lecteurs d'écran, sans couleurs, liens ni menus
+editor-ask-furigana = Afficher les furigana après la réponse
+editor-ask-close-distance = Presque, à moins de ce nombre de modifications
+editor-ask-case-sensitive = Compter les erreurs de majuscules
+editor-ask-accent-sensitive = Compter les erreurs d'accents
+editor-ask-scheduler = Comment les révisions sont planifiées
+editor-ask-direction = Sens
+editor-ask-strategy = Quel mot masquer
+editor-ask-dictionary = Vers où les mots renvoient
+editor-dictionary-help = wiktionary, wiktionary:<édition>, jisho, ou une URL avec { "{word}" }
+editor-unknown-dictionary = minicloze ne connaît pas ce dictionnaire
+editor-at-least-one = Il faut au moins 1
+editor-which-language = Quelle langue ?
+editor-overrides-for = Réglages pour { $language }
+editor-override-native = Langue maternelle
+editor-override = Remplacer
+editor-change = Le modifier
+editor-use-default = Utiliser la valeur par défaut
@@ -0,0 +1,354 @@
+// word alignment between sentences and their english translations, using IBM Model 1
+// (https://en.wikipedia.org/wiki/IBM_alignment_models#Model_1). every sentence pair minicloze
+// fetches or imports is kept in corpus/<tatoeba code>.tsv in the data dir, one
+// "id<TAB>english<TAB>translation" line per pair. training takes a while on a big corpus, so
+// the model is kept in alignment/<tatoeba code>.json, and only retrained after an import or
+// once the corpus has grown by RETRAIN_GROWTH
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::langs::is_spaced;
+use crate::paths::data_file;
+use crate::sentence::{convert_error, remove_punctuation, Sentence};
+
+// rounds of expectation maximisation, model 1 converges quickly
+const ITERATIONS: usize = 5;
+
+// below this translation probability a word isn't considered aligned at all
+const MIN_PROBABILITY: f64 = 0.05;
+
+// how much the corpus grows, as a share of the pairs the kept model was trained on, before
+// it's retrained
+const RETRAIN_GROWTH: f64 = 0.25;
+
+// t(english word | foreign word), with words numbered to keep training cheap. foreign word 0
+// stands in for english words the translation has no counterpart for
+pub struct AlignmentModel {
+    english_ids: HashMap<String, usize>,
+    foreign_ids: HashMap<String, usize>,
+    probabilities: HashMap<(usize, usize), f64>,
+}
+
+// a model as it's kept, with the size of the corpus it was trained on. probabilities below
+// MIN_PROBABILITY never decide an alignment, so they're left out
+#[derive(Deserialize, Serialize)]
+struct Saved {
+    pairs: usize,
+    english_ids: HashMap<String, usize>,
+    foreign_ids: HashMap<String, usize>,
+    probabilities: Vec<(usize, usize, f64)>,
+}
+
+// a pair of sentences as word numbers
+struct Pair {
+    english: Vec<usize>,
+    foreign: Vec<usize>,
+}
+
+fn corpus(language: &str) -> Result<String, String> {
+    Ok(fs::read_to_string(data_file("corpus", &format!("{language}.tsv"))?).unwrap_or_default())
+}
+
+impl AlignmentModel {
+    // the kept model for the language, trained first if there isn't one or it's out of date
+    pub fn load(language: &str) -> Result<AlignmentModel, String> {
+        let pairs = corpus(language)?.lines().count();
+        let path = data_file("alignment", &format!("{language}.json"))?;
+
+        let saved = fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Saved>(&json).ok());
+
+        match saved {
+            Some(saved) if (pairs as f64) < saved.pairs as f64 * (1.0 + RETRAIN_GROWTH) => {
+                Ok(AlignmentModel {
+                    english_ids: saved.english_ids,
+                    foreign_ids: saved.foreign_ids,
+                    probabilities: saved
+                        .probabilities
+                        .into_iter()
+                        .map(|(e, f, p)| ((e, f), p))
+                        .collect(),
+                })
+            }
+            _ => AlignmentModel::train(language),
+        }
+    }
+
+    // trains on the stored corpus for the language, and keeps the model
+    pub fn train(language: &str) -> Result<AlignmentModel, String> {
+        let corpus = corpus(language)?;
+
+        let mut english_ids = HashMap::new();
+        let mut foreign_ids = HashMap::from([(String::new(), 0)]);
+
+        let number = |ids: &mut HashMap<String, usize>, words: Vec<String>| {
+            words
+                .into_iter()
+                .map(|word| {
+                    let next = ids.len();
+                    *ids.entry(word).or_insert(next)
+                })
+                .collect()
+        };
+
+        let pairs: Vec<Pair> = corpus
+            .lines()
+            .filter_map(|line| {
+                let mut columns = line.split('\t').skip(1);
+                let english = columns.next()?;
+                let foreign = columns.next()?;
+
+                Some(Pair {
+                    english: number(&mut english_ids, tokenise(english, "eng")),
+                    foreign: number(&mut foreign_ids, tokenise(foreign, language)),
+                })
+            })
+            .collect();
+
+        let model = AlignmentModel {
+            english_ids,
+            foreign_ids,
+            probabilities: train(&pairs),
+        };
+
+        let saved = Saved {
+            pairs: pairs.len(),
+            english_ids: model.english_ids.clone(),
+            foreign_ids: model.foreign_ids.clone(),
+            probabilities: model
+                .probabilities
+                .iter()
+                .filter(|(_, p)| **p >= MIN_PROBABILITY)
+                .map(|((e, f), p)| (*e, *f, *p))
+                .collect(),
+        };
+        let json = serde_json::to_string(&saved).map_err(convert_error)?;
+        fs::write(data_file("alignment", &format!("{language}.json"))?, json)
+            .map_err(|e| e.to_string())?;
+
+        Ok(model)
+    }
+
+    fn probability(&self, english: &str, foreign: &str) -> f64 {
+        let (Some(e), Some(f)) = (self.english_ids.get(english), self.foreign_ids.get(foreign))
+        else {
+            return 0.0;
+        };
+
+        self.probabilities.get(&(*e, *f)).copied().unwrap_or(0.0)
+    }
+
+    // positions in the english tokens which the foreign word most likely translates to
+    pub fn align_foreign(&self, word: &str, english: &[String]) -> Vec<usize> {
+        let word = word.trim().to_lowercase();
+        best_positions(english.iter().map(|e| self.probability(e, &word)))
+    }
+
+    // positions in the foreign tokens which the english word most likely translates
+    pub fn align_english(&self, word: &str, foreign: &[String]) -> Vec<usize> {
+        let word = word.trim().to_lowercase();
+        best_positions(foreign.iter().map(|f| self.probability(&word, f)))
+    }
+}
+
+// expectation maximisation, starting with every co-occurring pair equally likely
+fn train(pairs: &[Pair]) -> HashMap<(usize, usize), f64> {
+    let mut probabilities: HashMap<(usize, usize), f64> = HashMap::new();
+
+    for pair in pairs {
+        for e in &pair.english {
+            for f in pair.foreign.iter().chain([&0]) {
+                probabilities.insert((*e, *f), 1.0);
+            }
+        }
+    }
+
+    for _ in 0..ITERATIONS {
+        let mut counts: HashMap<(usize, usize), f64> = HashMap::new();
+        let mut totals: HashMap<usize, f64> = HashMap::new();
+
+        for pair in pairs {
+            let foreign: Vec<usize> = pair.foreign.iter().copied().chain([0]).collect();
+
+            for e in &pair.english {
+                let normaliser: f64 = foreign.iter().map(|f| probabilities[&(*e, *f)]).sum();
+
+                for f in &foreign {
+                    let share = probabilities[&(*e, *f)] / normaliser;
+
+                    *counts.entry((*e, *f)).or_default() += share;
+                    *totals.entry(*f).or_default() += share;
+                }
+            }
+        }
+
+        for ((e, f), count) in counts {
+            probabilities.insert((e, f), count / totals[&f]);
+        }
+    }
+
+    probabilities
+}
+
+// lowercased words without punctuation, or characters for unspaced languages
+pub fn tokenise(text: &str, language: &str) -> Vec<String> {
+    if !is_spaced(language) {
+        remove_punctuation(text)
+            .chars()
+            .map(|c| c.to_lowercase().to_string())
+            .collect()
+    } else {
+        text.split_whitespace()
+            .map(|word| remove_punctuation(word).to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect()
+    }
+}
+
+// adds the round's sentences to the corpus, skipping pairs it already has
+pub fn record_pairs(language: &str, sentences: &[Sentence]) -> Result<(), String> {
+    let existing = corpus(language)?;
+    let mut known = known_pairs(&existing);
+    let mut lines = String::new();
+
+    for sentence in sentences {
+        let Some(translation) = sentence.get_translation() else {
+            continue;
+        };
+
+        let line = corpus_line(&sentence.id.to_string(), &sentence.text, &translation.text);
+        if known.insert(pair_of(&line)) {
+            lines.push_str(&line);
+        }
+    }
+
+    append(&data_file("corpus", &format!("{language}.tsv"))?, &lines)
+}
+
+// imports pairs from a tab-separated file, either "translation<TAB>english" or tatoeba's
+// "id<TAB>translation<TAB>id<TAB>english" pair export, and retrains the model. pairs the
+// corpus already has are skipped. returns how many were imported
+pub fn import_pairs(language: &str, source: &Path) -> Result<usize, String> {
+    let text = fs::read_to_string(source)
+        .map_err(|e| format!("Couldn't read {}: {e}", source.display()))?;
+
+    let existing = corpus(language)?;
+    let mut known = known_pairs(&existing);
+    let mut lines = String::new();
+    let mut count = 0;
+
+    for (number, line) in text.lines().enumerate() {
+        let columns: Vec<&str> = line.split('\t').collect();
+
+        let (id, foreign, english) = match columns.as_slice() {
+            [id, foreign, _, english] => (id.to_string(), *foreign, *english),
+            [foreign, english] => (format!("import-{number}"), *foreign, *english),
+            _ => continue,
+        };
+
+        let line = corpus_line(&id, english, foreign);
+        if known.insert(pair_of(&line)) {
+            lines.push_str(&line);
+            count += 1;
+        }
+    }
+
+    append(&data_file("corpus", &format!("{language}.tsv"))?, &lines)?;
+    AlignmentModel::train(language)?;
+
+    Ok(count)
+}
+
+// the english and foreign text of every pair in a corpus
+fn known_pairs(corpus: &str) -> HashSet<(String, String)> {
+    corpus.lines().map(pair_of).collect()
+}
+
+// a corpus line's english and foreign text, which identify the pair whatever its id
+fn pair_of(line: &str) -> (String, String) {
+    let mut columns = line.trim_end_matches('\n').split('\t').skip(1);
+    let english = columns.next().unwrap_or_default();
+    let foreign = columns.next().unwrap_or_default();
+
+    (english.to_string(), foreign.to_string())
+}
+
+// tabs and newlines would break the format, tatoeba sentences shouldn't have any anyway
+fn corpus_line(id: &str, english: &str, foreign: &str) -> String {
+    let clean = |text: &str| text.replace(['\t', '\n'], " ");
+    format!("{id}\t{}\t{}\n", clean(english), clean(foreign))
+}
+
+fn append(path: &Path, lines: &str) -> Result<(), String> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .map_err(|e| format!("Couldn't write to {}: {e}", path.display()))
+}
+
+// the positions sharing the highest probability, if it's high enough
+fn best_positions(probabilities: impl Iterator<Item = f64>) -> Vec<usize> {
+    let probabilities: Vec<f64> = probabilities.collect();
+    let best = probabilities.iter().copied().fold(0.0, f64::max);
+
+    if best < MIN_PROBABILITY {
+        return Vec::new();
+    }
+
+    probabilities
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| (best - **p).abs() < f64::EPSILON)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::isolate;
+
+    #[test]
+    fn aligns_a_tiny_corpus() {
+        isolate();
+        let source = data_file("corpus", "nld-source.tsv").unwrap();
+        fs::write(
+            &source,
+            "het huis\tthe house\nhet boek\tthe book\neen boek\ta book\nHet boek!\tThe book.\n",
+        )
+        .unwrap();
+
+        // pairs are told apart as they're written, so the last one isn't the second again
+        assert_eq!(import_pairs("nld", &source), Ok(4));
+        assert_eq!(import_pairs("nld", &source), Ok(0));
+
+        let model = AlignmentModel::load("nld").unwrap();
+        let words = |text: &str| tokenise(text, "eng");
+
+        assert_eq!(
+            model.align_english("house", &tokenise("het huis", "nld")),
+            [1]
+        );
+        assert_eq!(
+            model.align_english("The", &tokenise("het boek", "nld")),
+            [0]
+        );
+        assert_eq!(model.align_foreign("boek", &words("a book")), [1]);
+        assert_eq!(model.align_foreign("een", &words("a book")), [0]);
+        assert!(model.align_foreign("kat", &words("a book")).is_empty());
+    }
+
+    #[test]
+    fn tokenises_spaced_and_unspaced_text() {
+        assert_eq!(tokenise("Het boek, toch?", "nld"), ["het", "boek", "toch"]);
+        assert_eq!(tokenise("猫が好き。", "jpn"), ["猫", "が", "好", "き"]);
+    }
+}
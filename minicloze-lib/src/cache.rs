@@ -0,0 +1,104 @@
+// sentences kept for offline play. every pair of languages has its own file,
+// cache/<native>-<language>.json in the data dir, holding the sentences as tatoeba sent them
+
+use std::fs;
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::paths::{data_dir, data_file};
+use crate::sentence::{convert_error, Sentence};
+
+fn file_name(language: &str, native: &str) -> String {
+    format!("{native}-{language}.json")
+}
+
+pub fn load(language: &str, native: &str) -> Result<Vec<Sentence>, String> {
+    let path = data_file("cache", &file_name(language, native))?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(convert_error)
+}
+
+// adds sentences the cache doesn't have yet, returns how many were new
+pub fn store(language: &str, native: &str, sentences: &[Sentence]) -> Result<usize, String> {
+    let mut cached = load(language, native)?;
+    let before = cached.len();
+
+    for sentence in sentences {
+        if !cached.iter().any(|s| s.id == sentence.id) {
+            cached.push(sentence.clone());
+        }
+    }
+
+    let json = serde_json::to_string(&cached).map_err(convert_error)?;
+    fs::write(data_file("cache", &file_name(language, native))?, json)
+        .map_err(|e| e.to_string())?;
+
+    Ok(cached.len() - before)
+}
+
+// a round's worth of cached sentences, in random order
+pub fn sample(
+    language: &str,
+    native: &str,
+    count: usize,
+    rng: &mut impl Rng,
+) -> Result<Vec<Sentence>, String> {
+    let cached = load(language, native)?;
+
+    if cached.is_empty() {
+        return Err(format!(
+            "No cached sentences for {language}, fetch some with `minicloze cache fetch`"
+        ));
+    }
+
+    Ok(cached.choose_multiple(rng, count).cloned().collect())
+}
+
+// (language, native, number of sentences) for every cache file
+pub fn list() -> Result<Vec<(String, String, usize)>, String> {
+    let Some(dir) = data_dir().map(|dir| dir.join("cache")) else {
+        return Ok(Vec::new());
+    };
+    let Ok(files) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut caches = Vec::new();
+
+    for file in files.filter_map(Result::ok) {
+        let name = file.file_name().to_string_lossy().to_string();
+
+        let Some((native, language)) = name
+            .strip_suffix(".json")
+            .and_then(|stem| stem.split_once('-'))
+        else {
+            continue;
+        };
+
+        let count = load(language, native)?.len();
+        caches.push((language.to_string(), native.to_string(), count));
+    }
+
+    caches.sort();
+    Ok(caches)
+}
+
+// deletes the cache for one language, or all of them. returns how many files were removed
+pub fn clear(language: Option<&str>) -> Result<usize, String> {
+    let mut removed = 0;
+
+    for (cached_language, native, _) in list()? {
+        if language.is_none_or(|l| l == cached_language) {
+            fs::remove_file(data_file("cache", &file_name(&cached_language, &native))?)
+                .map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
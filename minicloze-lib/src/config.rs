@@ -0,0 +1,201 @@
+// settings kept in config.toml in the config dir, e.g. ~/.config/minicloze/config.toml on linux.
+// every key is optional, and anything under [languages.<language>] overrides the defaults for
+// that language, e.g.
+//
+//     language = "French"
+//     round_size = 15
+//
+//     [languages.German.grading]
+//     case_sensitive = true
+//
+//     [languages.Japanese]
+//     furigana = true
+
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::grading::GradingPolicy;
+use crate::paths::config_file;
+use crate::resolve::resolve_language;
+use crate::sentence::{Strategy, ROUND_SIZE};
+use crate::srs::Scheduler;
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Config {
+    // played when no language is given, asked for if None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    // the language translations are shown in
+    pub native: String,
+    // fill in the native language instead of the target one
+    pub inverse: bool,
+    pub round_size: usize,
+    pub strategy: Strategy,
+    // a built-in colour theme or one of themes
+    pub theme: String,
+    // no colours, links or screen clearing, and the blank spelled out, for screen readers
+    pub plain: bool,
+    // see dictionary::provider_by_name
+    pub dictionary: String,
+    // show the reading of japanese sentences after answering
+    pub furigana: bool,
+    pub grading: GradingPolicy,
+    // see srs.rs
+    pub scheduler: Scheduler,
+    // keyed by anything resolve_language accepts
+    pub languages: BTreeMap<String, LanguageConfig>,
+    // user-defined colour themes, each a map of parts of the game to styles. it's up to the
+    // frontend what they mean
+    pub themes: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+// per-language overrides, None keeps the default
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct LanguageConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub native: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inverse: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_size: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<Strategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dictionary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub furigana: Option<bool>,
+    #[serde(skip_serializing_if = "GradingOverride::is_empty")]
+    pub grading: GradingOverride,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct GradingOverride {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_distance: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub case_sensitive: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accent_sensitive: Option<bool>,
+}
+
+// the settings for one language, with its overrides applied
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub native: String,
+    pub inverse: bool,
+    pub round_size: usize,
+    pub strategy: Strategy,
+    pub dictionary: String,
+    pub furigana: bool,
+    pub grading: GradingPolicy,
+    pub scheduler: Scheduler,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            language: None,
+            native: String::from("English"),
+            inverse: false,
+            round_size: ROUND_SIZE,
+            strategy: Strategy::Random,
+            theme: String::from("dark"),
+            plain: false,
+            dictionary: String::from("wiktionary"),
+            furigana: false,
+            grading: GradingPolicy::default(),
+            scheduler: Scheduler::default(),
+            languages: BTreeMap::new(),
+            themes: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    // the defaults if there's no config file yet
+    pub fn load() -> Result<Config, String> {
+        let path = config_file()?;
+
+        let config: Config = match fs::read_to_string(&path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|e| format!("Couldn't read {}: {}", path.display(), e.message()))?,
+            Err(_) => return Ok(Config::default()),
+        };
+
+        config
+            .validate()
+            .map_err(|e| format!("Couldn't read {}: {e}", path.display()))?;
+        Ok(config)
+    }
+
+    // what toml's types can't rule out
+    fn validate(&self) -> Result<(), String> {
+        let sizes = [(String::new(), Some(self.round_size))].into_iter().chain(
+            self.languages
+                .iter()
+                .map(|(language, overrides)| (format!(" for {language}"), overrides.round_size)),
+        );
+
+        for (place, size) in sizes {
+            if size == Some(0) {
+                return Err(format!("round_size{place} has to be at least 1"));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = config_file()?;
+        let text = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+
+        fs::write(&path, text).map_err(|e| format!("Couldn't write {}: {e}", path.display()))
+    }
+
+    // the overrides for a tatoeba code, whichever way the config names the language
+    pub fn overrides(&self, language: &str) -> Option<&LanguageConfig> {
+        self.languages.iter().find_map(|(key, overrides)| {
+            let code = resolve_language(key).ok()?.code;
+            (code == language).then_some(overrides)
+        })
+    }
+
+    pub fn settings(&self, language: &str) -> Settings {
+        let overrides = self.overrides(language).cloned().unwrap_or_default();
+        let grading = overrides.grading;
+
+        Settings {
+            native: overrides.native.unwrap_or_else(|| self.native.clone()),
+            inverse: overrides.inverse.unwrap_or(self.inverse),
+            round_size: overrides.round_size.unwrap_or(self.round_size),
+            strategy: overrides.strategy.unwrap_or(self.strategy),
+            dictionary: overrides
+                .dictionary
+                .unwrap_or_else(|| self.dictionary.clone()),
+            furigana: overrides.furigana.unwrap_or(self.furigana),
+            grading: GradingPolicy {
+                close_distance: grading
+                    .close_distance
+                    .unwrap_or(self.grading.close_distance),
+                case_sensitive: grading
+                    .case_sensitive
+                    .unwrap_or(self.grading.case_sensitive),
+                accent_sensitive: grading
+                    .accent_sensitive
+                    .unwrap_or(self.grading.accent_sensitive),
+            },
+            scheduler: self.scheduler,
+        }
+    }
+}
+
+impl GradingOverride {
+    pub fn is_empty(&self) -> bool {
+        *self == GradingOverride::default()
+    }
+}
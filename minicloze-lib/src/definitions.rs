@@ -0,0 +1,273 @@
+// offline definitions from a wiktextract extract, see https://kaikki.org
+// the extracts are one JSON object per line and far too big to load every game, so importing
+// keeps only what minicloze shows and writes it to definitions/<tatoeba code>.json in the data
+// dir. a raw extract left at wiktextract/<tatoeba code>.jsonl is imported on first use. if that
+// fails, why is written to definitions/<tatoeba code>.failed, and the extract isn't scanned
+// again until it's replaced
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::langs::Language;
+use crate::paths::data_file;
+use crate::sentence::convert_error;
+
+// how many senses are kept per entry
+const MAX_GLOSSES: usize = 3;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Definition {
+    pub pos: String,
+    pub gender: Option<String>,
+    pub ipa: Option<String>,
+    pub glosses: Vec<String>,
+}
+
+pub struct Definitions {
+    // headword -> its entries, one per part of speech
+    entries: HashMap<String, Vec<Definition>>,
+}
+
+// the parts of a wiktextract line minicloze uses, everything else is skipped by serde
+#[derive(Deserialize)]
+struct RawEntry {
+    word: String,
+    #[serde(default)]
+    lang: String,
+    #[serde(default)]
+    pos: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    senses: Vec<RawSense>,
+    #[serde(default)]
+    sounds: Vec<RawSound>,
+}
+
+#[derive(Deserialize)]
+struct RawSense {
+    #[serde(default)]
+    glosses: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct RawSound {
+    ipa: Option<String>,
+}
+
+impl Definition {
+    // e.g. "noun (m) /ʃa/: cat; tomcat"
+    pub fn short(&self) -> String {
+        let mut head = self.pos.clone();
+
+        if let Some(gender) = &self.gender {
+            head.push_str(&format!(" ({gender})"));
+        }
+        if let Some(ipa) = &self.ipa {
+            head.push_str(&format!(" {ipa}"));
+        }
+
+        // local dictionaries only have the text of the article
+        if head.is_empty() {
+            return self.glosses.join("; ");
+        }
+
+        format!("{head}: {}", self.glosses.join("; "))
+    }
+}
+
+impl Definitions {
+    pub fn load(language: &str) -> Result<Option<Definitions>, String> {
+        let path = data_file("definitions", &format!("{language}.json"))?;
+
+        if !path.exists() {
+            let raw = data_file("wiktextract", &format!("{language}.jsonl"))?;
+
+            if !raw.exists() {
+                return Ok(None);
+            }
+
+            let failed = data_file("definitions", &format!("{language}.failed"))?;
+            if is_newer(&failed, &raw) {
+                return Ok(None);
+            }
+
+            if let Err(e) = Definitions::import(&raw, language) {
+                fs::write(&failed, &e).map_err(|e| e.to_string())?;
+                return Err(e);
+            }
+        }
+
+        let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        let entries = serde_json::from_str(&json).map_err(convert_error)?;
+
+        Ok(Some(Definitions { entries }))
+    }
+
+    // reads a wiktextract JSONL file, keeping the entries for one language.
+    // returns how many headwords were imported
+    pub fn import(source: &Path, language: &str) -> Result<usize, String> {
+        let file =
+            File::open(source).map_err(|e| format!("Couldn't read {}: {e}", source.display()))?;
+
+        // wiktextract names languages after english wiktionary's sections
+        let name = Language::from_code(language)
+            .map(|language| language.wiktionary)
+            .ok_or(format!("{language} isn't a Tatoeba language code"))?;
+
+        let mut entries: HashMap<String, Vec<Definition>> = HashMap::new();
+
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| e.to_string())?;
+            let Ok(raw) = serde_json::from_str::<RawEntry>(&line) else {
+                continue;
+            };
+
+            // per-language extracts all match, full dumps need filtering
+            if !raw.lang.is_empty() && raw.lang != name {
+                continue;
+            }
+
+            if let Some(definition) = compact(&raw) {
+                entries.entry(raw.word).or_default().push(definition);
+            }
+        }
+
+        if entries.is_empty() {
+            return Err(format!("No {name} entries found in {}", source.display()));
+        }
+
+        let json = serde_json::to_string(&entries).map_err(convert_error)?;
+        fs::write(data_file("definitions", &format!("{language}.json"))?, json)
+            .map_err(|e| e.to_string())?;
+
+        Ok(entries.len())
+    }
+
+    // looks the word up as written, then lowercased for sentence-initial words
+    pub fn lookup(&self, word: &str) -> Option<&[Definition]> {
+        let word = word.trim();
+
+        self.entries
+            .get(word)
+            .or_else(|| self.entries.get(&word.to_lowercase()))
+            .map(Vec::as_slice)
+    }
+}
+
+fn compact(raw: &RawEntry) -> Option<Definition> {
+    let glosses: Vec<String> = raw
+        .senses
+        .iter()
+        .filter_map(|sense| sense.glosses.last().cloned())
+        .take(MAX_GLOSSES)
+        .collect();
+
+    if glosses.is_empty() {
+        return None;
+    }
+
+    let tags = raw
+        .tags
+        .iter()
+        .chain(raw.senses.iter().flat_map(|sense| sense.tags.iter()));
+
+    let mut genders: Vec<&str> = Vec::new();
+    for tag in tags {
+        let gender = match tag.as_str() {
+            "masculine" => "m",
+            "feminine" => "f",
+            "neuter" => "n",
+            "common" => "c",
+            _ => continue,
+        };
+        if !genders.contains(&gender) {
+            genders.push(gender);
+        }
+    }
+
+    Some(Definition {
+        pos: raw.pos.clone(),
+        gender: (!genders.is_empty()).then(|| genders.join("/")),
+        ipa: raw.sounds.iter().find_map(|sound| sound.ipa.clone()),
+        glosses,
+    })
+}
+
+// whether the file at path was written after the one at than, false if either is missing
+fn is_newer(path: &Path, than: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    match (modified(path), modified(than)) {
+        (Some(path), Some(than)) => path >= than,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::FileTimes;
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::paths::isolate;
+
+    const DUTCH: &str = r#"{"word": "kat", "lang": "Dutch", "pos": "noun", "tags": ["feminine"], "sounds": [{"ipa": "/kɑt/"}], "senses": [{"glosses": ["cat"], "tags": ["masculine"]}, {"glosses": ["a spiteful woman"]}]}
+{"word": "kat", "lang": "German", "pos": "noun", "senses": [{"glosses": ["not dutch"]}]}
+{"word": "lopen", "lang": "Dutch", "pos": "verb", "senses": [{"glosses": ["to walk"]}, {"glosses": ["to run"]}, {"glosses": ["to flow"]}, {"glosses": ["to go"]}]}
+{"word": "leeg", "lang": "Dutch", "pos": "adj", "senses": []}
+not json
+"#;
+
+    #[test]
+    fn imports_a_wiktextract_extract() {
+        isolate();
+        let source = data_file("wiktextract", "nld-source.jsonl").unwrap();
+        fs::write(&source, DUTCH).unwrap();
+
+        // a headword without glosses is dropped, and so is the german line
+        assert_eq!(Definitions::import(&source, "nld"), Ok(2));
+
+        let definitions = Definitions::load("nld").unwrap().unwrap();
+        let kat = definitions.lookup("Kat").unwrap();
+        assert_eq!(kat.len(), 1);
+        assert_eq!(kat[0].short(), "noun (f/m) /kɑt/: cat; a spiteful woman");
+
+        let lopen = &definitions.lookup(" lopen ").unwrap()[0];
+        assert_eq!(lopen.glosses, ["to walk", "to run", "to flow"]);
+        assert!(definitions.lookup("leeg").is_none());
+    }
+
+    #[test]
+    fn remembers_a_failed_import_until_the_extract_changes() {
+        isolate();
+        let raw = data_file("wiktextract", "swe.jsonl").unwrap();
+        let failed = data_file("definitions", "swe.failed").unwrap();
+        fs::write(&raw, DUTCH).unwrap();
+
+        assert!(Definitions::load("swe").is_err());
+        assert!(failed.exists());
+
+        // the extract isn't scanned again
+        assert!(Definitions::load("swe").unwrap().is_none());
+
+        let swedish = r#"{"word": "katt", "lang": "Swedish", "pos": "noun", "senses": [{"glosses": ["cat"]}]}"#;
+        fs::write(&raw, swedish).unwrap();
+        let later = SystemTime::now() + Duration::from_secs(60);
+        File::options()
+            .write(true)
+            .open(&raw)
+            .unwrap()
+            .set_times(FileTimes::new().set_modified(later))
+            .unwrap();
+
+        let definitions = Definitions::load("swe").unwrap().unwrap();
+        assert_eq!(definitions.lookup("katt").unwrap()[0].glosses, ["cat"]);
+    }
+}
@@ -0,0 +1,301 @@
+// where words get looked up: online dictionaries give links, local ones give definitions
+
+use crate::definitions::{Definition, Definitions};
+use crate::langs::{is_spaced, Language};
+use crate::localdict::LocalDictionary;
+use crate::sentence::remove_punctuation;
+
+// the longest run of characters tried as one word when glossing unspaced languages
+const MAX_UNSPACED_WORD: usize = 6;
+
+pub trait DictionaryProvider: Send + Sync {
+    // shown to the user, e.g. "wiktionary"
+    fn name(&self) -> String;
+
+    // a link to the word's entry, None if the provider doesn't cover the language
+    fn url(&self, word: &str, language: &str) -> Option<String>;
+
+    // definitions available offline, only local dictionaries have these
+    fn lookup(&self, _word: &str, _language: &str) -> Vec<Definition> {
+        Vec::new()
+    }
+}
+
+// edition is the wiktionary's own language, e.g. "en" for en.wiktionary.org
+pub struct Wiktionary {
+    pub edition: String,
+}
+
+// jisho.org, only covers japanese
+pub struct Jisho;
+
+// any site with a search url. {word} and {lang} (the tatoeba code) are substituted,
+// e.g. "https://www.wordreference.com/fren/{word}"
+pub struct UrlTemplate {
+    pub template: String,
+    // None means every language
+    pub languages: Option<Vec<String>>,
+}
+
+impl DictionaryProvider for Wiktionary {
+    fn name(&self) -> String {
+        if self.edition == "en" {
+            String::from("wiktionary")
+        } else {
+            format!("wiktionary:{}", self.edition)
+        }
+    }
+
+    fn url(&self, word: &str, language: &str) -> Option<String> {
+        let mut url = format!(
+            "https://{}.wiktionary.org/wiki/{}",
+            self.edition,
+            encode(word)
+        );
+
+        // other editions name their sections in their own language, so only english
+        // wiktionary can jump to the right one
+        if self.edition == "en" {
+            if let Some(language) = Language::from_code(language) {
+                url.push('#');
+                url.push_str(&language.wiktionary.replace(' ', "_"));
+            }
+        }
+
+        Some(url)
+    }
+}
+
+impl DictionaryProvider for Jisho {
+    fn name(&self) -> String {
+        String::from("jisho")
+    }
+
+    fn url(&self, word: &str, language: &str) -> Option<String> {
+        (language == "jpn").then(|| format!("https://jisho.org/search/{}", encode(word)))
+    }
+}
+
+impl DictionaryProvider for UrlTemplate {
+    fn name(&self) -> String {
+        self.template.clone()
+    }
+
+    fn url(&self, word: &str, language: &str) -> Option<String> {
+        if let Some(languages) = &self.languages {
+            if !languages.iter().any(|l| l == language) {
+                return None;
+            }
+        }
+
+        Some(
+            self.template
+                .replace("{word}", &encode(word))
+                .replace("{lang}", language),
+        )
+    }
+}
+
+impl DictionaryProvider for Definitions {
+    fn name(&self) -> String {
+        String::from("wiktextract")
+    }
+
+    fn url(&self, _word: &str, _language: &str) -> Option<String> {
+        None
+    }
+
+    fn lookup(&self, word: &str, _language: &str) -> Vec<Definition> {
+        Definitions::lookup(self, word).map_or_else(Vec::new, <[Definition]>::to_vec)
+    }
+}
+
+// the word percent-encoded for a url, so e.g. "?" or "#" in it don't end its path. only
+// letters, digits and "-._~" are left as they are
+pub fn encode(word: &str) -> String {
+    let mut encoded = String::new();
+
+    for byte in word.trim().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    encoded
+}
+
+// a provider from a name the user gave: "wiktionary", "wiktionary:<edition>", "jisho", or a
+// url template containing {word}
+pub fn provider_by_name(name: &str) -> Option<Box<dyn DictionaryProvider>> {
+    let name = name.trim();
+
+    if name.contains("{word}") {
+        return Some(Box::new(UrlTemplate {
+            template: name.to_string(),
+            languages: None,
+        }));
+    }
+
+    match name.to_lowercase().as_str() {
+        "wiktionary" => Some(Box::new(Wiktionary {
+            edition: String::from("en"),
+        })),
+        "jisho" => Some(Box::new(Jisho)),
+        other => other
+            .strip_prefix("wiktionary:")
+            .filter(|edition| !edition.is_empty())
+            .map(|edition| {
+                Box::new(Wiktionary {
+                    edition: edition.to_string(),
+                }) as Box<dyn DictionaryProvider>
+            }),
+    }
+}
+
+// the provider's link, falling back to english wiktionary for languages it doesn't cover
+pub fn url_or_wiktionary(provider: &dyn DictionaryProvider, word: &str, language: &str) -> String {
+    provider.url(word, language).unwrap_or_else(|| {
+        Wiktionary {
+            edition: String::from("en"),
+        }
+        .url(word, language)
+        .unwrap_or_default()
+    })
+}
+
+// every dictionary with offline definitions for the language: the wiktextract extract, then
+// any StarDict or dictd dictionaries
+pub fn local_dictionaries(language: &str) -> Vec<Box<dyn DictionaryProvider>> {
+    let mut dictionaries: Vec<Box<dyn DictionaryProvider>> = Vec::new();
+
+    if let Ok(Some(definitions)) = Definitions::load(language) {
+        dictionaries.push(Box::new(definitions));
+    }
+
+    for dictionary in LocalDictionary::load_all(language) {
+        dictionaries.push(Box::new(dictionary));
+    }
+
+    dictionaries
+}
+
+// definitions of a word from the first dictionary that has it
+pub fn lookup_first(
+    dictionaries: &[Box<dyn DictionaryProvider>],
+    word: &str,
+    language: &str,
+) -> Vec<Definition> {
+    dictionaries
+        .iter()
+        .map(|dictionary| dictionary.lookup(word, language))
+        .find(|definitions| !definitions.is_empty())
+        .unwrap_or_default()
+}
+
+// splits text into words and looks each up. unspaced languages are split greedily into the
+// longest runs of characters some dictionary knows
+pub fn gloss_words(
+    dictionaries: &[Box<dyn DictionaryProvider>],
+    text: &str,
+    language: &str,
+) -> Vec<(String, Vec<Definition>)> {
+    if is_spaced(language) {
+        return text
+            .split_whitespace()
+            .map(remove_punctuation)
+            .filter(|word| !word.is_empty())
+            .map(|word| {
+                let definitions = lookup_first(dictionaries, &word, language);
+                (word, definitions)
+            })
+            .collect();
+    }
+
+    let chars: Vec<char> = remove_punctuation(text).chars().collect();
+    let mut glosses = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let longest = (start + MAX_UNSPACED_WORD).min(chars.len());
+
+        let (end, definitions) = (start + 1..=longest)
+            .rev()
+            .map(|end| {
+                let word: String = chars[start..end].iter().collect();
+                (end, lookup_first(dictionaries, &word, language))
+            })
+            .find(|(_, definitions)| !definitions.is_empty())
+            .unwrap_or((start + 1, Vec::new()));
+
+        glosses.push((chars[start..end].iter().collect(), definitions));
+        start = end;
+    }
+
+    glosses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_words_for_urls() {
+        assert_eq!(encode(" chat "), "chat");
+        assert_eq!(encode("a-b_c.d~e"), "a-b_c.d~e");
+        assert_eq!(encode("qu'est-ce ?#"), "qu%27est-ce%20%3F%23");
+        assert_eq!(encode("été"), "%C3%A9t%C3%A9");
+        assert_eq!(encode("猫"), "%E7%8C%AB");
+    }
+
+    #[test]
+    fn finds_providers_by_name() {
+        let url = |name: &str, word: &str, language: &str| {
+            provider_by_name(name).unwrap().url(word, language)
+        };
+
+        assert_eq!(
+            url("Wiktionary", "été", "fra").unwrap(),
+            "https://en.wiktionary.org/wiki/%C3%A9t%C3%A9#French"
+        );
+        assert_eq!(
+            url("wiktionary:fr", "chat", "fra").unwrap(),
+            "https://fr.wiktionary.org/wiki/chat"
+        );
+        assert_eq!(
+            provider_by_name("wiktionary:fr").unwrap().name(),
+            "wiktionary:fr"
+        );
+        assert!(provider_by_name("wiktionary:").is_none());
+        assert!(provider_by_name("duden").is_none());
+
+        assert_eq!(
+            url("jisho", "猫", "jpn").unwrap(),
+            "https://jisho.org/search/%E7%8C%AB"
+        );
+        assert!(url("jisho", "chat", "fra").is_none());
+
+        assert_eq!(
+            url("https://example.com/{lang}/{word}", "a b", "fra").unwrap(),
+            "https://example.com/fra/a%20b"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_wiktionary() {
+        let template = UrlTemplate {
+            template: String::from("https://www.wordreference.com/fren/{word}"),
+            languages: Some(vec![String::from("fra")]),
+        };
+
+        assert_eq!(
+            url_or_wiktionary(&template, "chat", "fra"),
+            "https://www.wordreference.com/fren/chat"
+        );
+        assert_eq!(
+            url_or_wiktionary(&Jisho, "Hund", "deu"),
+            "https://en.wiktionary.org/wiki/Hund#German"
+        );
+    }
+}
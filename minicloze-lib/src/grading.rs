@@ -0,0 +1,405 @@
+// logic which grades a guess against the blanked word and explains what went wrong
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::i18n::Message;
+use crate::morph::{Inflection, Lexicon};
+use crate::sentence::remove_punctuation;
+
+// by default, guesses fewer than this many edits away from the word count as close
+pub const DISTANCE_FOR_CLOSE: usize = 3;
+
+// how strict grading is, set in the config file and per language
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct GradingPolicy {
+    // guesses fewer than this many edits away from the word count as close
+    pub close_distance: usize,
+    // whether a guess differing only in capitalisation is wrong, e.g. for german nouns
+    pub case_sensitive: bool,
+    // whether a guess differing only in diacritics is wrong
+    pub accent_sensitive: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grade {
+    Correct,
+    Close,
+    Wrong,
+}
+
+// what kind of mistake the learner made, most specific first
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mistake {
+    // only capitalisation differs
+    CaseOnly,
+    // a diacritic is missing, added or different, e.g. "ete" for "été"
+    AccentMissing,
+    // two neighbouring letters are swapped
+    Transposition,
+    // another form of the same lemma, only detected with a lexicon
+    WrongInflection,
+    // the stem is right but the ending isn't, usually a wrong inflection
+    WrongEnding,
+    DifferentWord,
+}
+
+// one step of the character alignment between the guess and the word
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffOp {
+    Same(char),
+    // guessed the first char where the second was expected
+    Substituted(char, char),
+    // a char of the word the guess left out
+    Missing(char),
+    // a char of the guess which isn't in the word
+    Extra(char),
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Feedback {
+    pub grade: Grade,
+    pub distance: usize,
+    // None when the guess was exactly right. a correct guess can still have a mistake the
+    // grading policy forgives, e.g. CaseOnly
+    pub mistake: Option<Mistake>,
+    pub diff: Vec<DiffOp>,
+    // set when the mistake is a WrongInflection
+    pub inflection: Option<Inflection>,
+}
+
+impl Default for GradingPolicy {
+    fn default() -> GradingPolicy {
+        GradingPolicy {
+            close_distance: DISTANCE_FOR_CLOSE,
+            // unlike the old exact match, "Le" for "le" is right. the mistake still says so
+            case_sensitive: false,
+            accent_sensitive: true,
+        }
+    }
+}
+
+impl GradingPolicy {
+    // the word with whatever the policy ignores taken out
+    fn fold(&self, word: &str) -> String {
+        let word = if self.case_sensitive {
+            word.to_string()
+        } else {
+            word.to_lowercase()
+        };
+
+        if self.accent_sensitive {
+            word
+        } else {
+            strip_accents(&word)
+        }
+    }
+}
+
+impl Mistake {
+    pub fn describe(&self) -> Message {
+        Message::new(match self {
+            Mistake::CaseOnly => "mistake-case-only",
+            Mistake::AccentMissing => "mistake-accent-missing",
+            Mistake::Transposition => "mistake-transposition",
+            Mistake::WrongInflection => "mistake-wrong-inflection",
+            Mistake::WrongEnding => "mistake-wrong-ending",
+            Mistake::DifferentWord => "mistake-different-word",
+        })
+    }
+}
+
+impl Feedback {
+    // what went wrong, None if nothing did
+    pub fn describe(&self) -> Option<Message> {
+        match (&self.inflection, self.mistake) {
+            (Some(inflection), _) => Some(inflection.describe()),
+            (None, Some(mistake)) => Some(mistake.describe()),
+            (None, None) => None,
+        }
+    }
+}
+
+// grades the raw user input against the blanked word
+pub fn grade(guess: &str, word: &str, policy: &GradingPolicy) -> Feedback {
+    let guess = remove_punctuation(guess.trim());
+    let word = word.trim();
+
+    let diff = diff(&guess, word);
+    let distance = edits(&diff);
+
+    let mistake = if distance == 0 {
+        None
+    } else {
+        Some(classify(&guess, word))
+    };
+
+    // the distance counts every differing char, even those the policy forgives
+    let grade = if policy.fold(&guess) == policy.fold(word) {
+        Grade::Correct
+    } else if distance < policy.close_distance {
+        Grade::Close
+    } else {
+        Grade::Wrong
+    };
+
+    Feedback {
+        grade,
+        distance,
+        mistake,
+        diff,
+        inflection: None,
+    }
+}
+
+// like grade, but recognises other inflections of the word using a UniMorph lexicon
+pub fn grade_with_lexicon(
+    guess: &str,
+    word: &str,
+    policy: &GradingPolicy,
+    lexicon: &Lexicon,
+) -> Feedback {
+    let mut feedback = grade(guess, word, policy);
+
+    if feedback.grade != Grade::Correct {
+        let inflection = lexicon.compare(&remove_punctuation(guess.trim()), word);
+
+        if inflection.is_some() {
+            feedback.mistake = Some(Mistake::WrongInflection);
+            feedback.inflection = inflection;
+        }
+    }
+
+    feedback
+}
+
+// works out which kind of mistake turns word into guess. assumes they differ
+pub fn classify(guess: &str, word: &str) -> Mistake {
+    let guess_lower = guess.to_lowercase();
+    let word_lower = word.to_lowercase();
+
+    if guess_lower == word_lower {
+        return Mistake::CaseOnly;
+    }
+
+    if strip_accents(&guess_lower) == strip_accents(&word_lower) {
+        return Mistake::AccentMissing;
+    }
+
+    let guess_chars: Vec<char> = guess_lower.chars().collect();
+    let word_chars: Vec<char> = word_lower.chars().collect();
+
+    if is_transposition(&guess_chars, &word_chars) {
+        return Mistake::Transposition;
+    }
+
+    // a shared stem covering at least half the word, e.g. "mange" for "mangeons"
+    let stem = guess_chars
+        .iter()
+        .zip(&word_chars)
+        .take_while(|(g, w)| g == w)
+        .count();
+    let shorter = guess_chars.len().min(word_chars.len());
+
+    if stem >= 3 && stem * 2 >= shorter {
+        return Mistake::WrongEnding;
+    }
+
+    Mistake::DifferentWord
+}
+
+// removes diacritics by decomposing and dropping the combining marks
+pub fn strip_accents(word: &str) -> String {
+    word.nfd()
+        .filter(|c| !('\u{0300}'..='\u{036f}').contains(c))
+        .nfc()
+        .collect()
+}
+
+fn is_transposition(guess: &[char], word: &[char]) -> bool {
+    if guess.len() != word.len() {
+        return false;
+    }
+
+    let differing: Vec<usize> = (0..word.len()).filter(|&i| guess[i] != word[i]).collect();
+
+    differing.len() == 2
+        && differing[1] == differing[0] + 1
+        && guess[differing[0]] == word[differing[1]]
+        && guess[differing[1]] == word[differing[0]]
+}
+
+// the levenshtein distance between two strings
+pub fn distance(a: &str, b: &str) -> usize {
+    edits(&diff(a, b))
+}
+
+fn edits(diff: &[DiffOp]) -> usize {
+    diff.iter()
+        .filter(|op| !matches!(op, DiffOp::Same(_)))
+        .count()
+}
+
+// aligns the guess with the word char by char, using the levenshtein table
+pub fn diff(guess: &str, word: &str) -> Vec<DiffOp> {
+    let guess: Vec<char> = guess.chars().collect();
+    let word: Vec<char> = word.chars().collect();
+
+    let width = word.len() + 1;
+    let mut table = vec![0; (guess.len() + 1) * width];
+
+    for i in 0..=guess.len() {
+        table[i * width] = i;
+    }
+    for (j, cell) in table.iter_mut().enumerate().take(width) {
+        *cell = j;
+    }
+
+    for i in 1..=guess.len() {
+        for j in 1..=word.len() {
+            let cost = usize::from(guess[i - 1] != word[j - 1]);
+
+            table[i * width + j] = (table[(i - 1) * width + j - 1] + cost)
+                .min(table[(i - 1) * width + j] + 1)
+                .min(table[i * width + j - 1] + 1);
+        }
+    }
+
+    // walk back from the bottom right corner, preferring substitutions
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (guess.len(), word.len());
+
+    while i > 0 || j > 0 {
+        let here = table[i * width + j];
+
+        if i > 0 && j > 0 {
+            let cost = usize::from(guess[i - 1] != word[j - 1]);
+
+            if here == table[(i - 1) * width + j - 1] + cost {
+                ops.push(if cost == 0 {
+                    DiffOp::Same(word[j - 1])
+                } else {
+                    DiffOp::Substituted(guess[i - 1], word[j - 1])
+                });
+                i -= 1;
+                j -= 1;
+                continue;
+            }
+        }
+
+        if i > 0 && here == table[(i - 1) * width + j] + 1 {
+            ops.push(DiffOp::Extra(guess[i - 1]));
+            i -= 1;
+        } else {
+            ops.push(DiffOp::Missing(word[j - 1]));
+            j -= 1;
+        }
+    }
+
+    ops.reverse();
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DiffOp::*;
+
+    #[test]
+    fn grades_and_classifies_guesses() {
+        let policy = GradingPolicy::default();
+        let table = [
+            ("été", "été", Grade::Correct, None),
+            ("été.", "été", Grade::Correct, None),
+            // capitalisation is forgiven by default, but still pointed out
+            ("Été", "été", Grade::Correct, Some(Mistake::CaseOnly)),
+            ("ete", "été", Grade::Close, Some(Mistake::AccentMissing)),
+            (
+                "mnager",
+                "manger",
+                Grade::Close,
+                Some(Mistake::Transposition),
+            ),
+            ("mangr", "manger", Grade::Close, Some(Mistake::WrongEnding)),
+            (
+                "mange",
+                "mangeons",
+                Grade::Wrong,
+                Some(Mistake::WrongEnding),
+            ),
+            ("chien", "chat", Grade::Wrong, Some(Mistake::DifferentWord)),
+        ];
+
+        for (guess, word, grade, mistake) in table {
+            let feedback = super::grade(guess, word, &policy);
+            assert_eq!(feedback.grade, grade, "{guess} for {word}");
+            assert_eq!(feedback.mistake, mistake, "{guess} for {word}");
+        }
+    }
+
+    #[test]
+    fn follows_the_policy() {
+        let strict = GradingPolicy {
+            case_sensitive: true,
+            ..GradingPolicy::default()
+        };
+        let lenient = GradingPolicy {
+            accent_sensitive: false,
+            ..GradingPolicy::default()
+        };
+
+        assert_eq!(grade("haus", "Haus", &strict).grade, Grade::Close);
+        assert_eq!(grade("ete", "été", &lenient).grade, Grade::Correct);
+        assert_eq!(
+            grade("ete", "été", &lenient).mistake,
+            Some(Mistake::AccentMissing)
+        );
+    }
+
+    #[test]
+    fn diffs_guesses() {
+        let table = [
+            (
+                "ete",
+                "été",
+                vec![Substituted('e', 'é'), Same('t'), Substituted('e', 'é')],
+            ),
+            (
+                "mnager",
+                "manger",
+                vec![
+                    Same('m'),
+                    Substituted('n', 'a'),
+                    Substituted('a', 'n'),
+                    Same('g'),
+                    Same('e'),
+                    Same('r'),
+                ],
+            ),
+            (
+                "mangr",
+                "manger",
+                vec![
+                    Same('m'),
+                    Same('a'),
+                    Same('n'),
+                    Same('g'),
+                    Missing('e'),
+                    Same('r'),
+                ],
+            ),
+            (
+                "chats",
+                "chat",
+                vec![Same('c'), Same('h'), Same('a'), Same('t'), Extra('s')],
+            ),
+            ("", "oui", vec![Missing('o'), Missing('u'), Missing('i')]),
+        ];
+
+        for (guess, word, ops) in table {
+            assert_eq!(diff(guess, word), ops, "{guess} for {word}");
+            assert_eq!(distance(guess, word), edits(&ops));
+        }
+    }
+}
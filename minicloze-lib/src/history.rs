@@ -0,0 +1,322 @@
+// every prompt ever answered, in an append-only log at history/answers.jsonl in the data dir.
+// the first line is a header with the schema version, every other line is a Record. a log
+// written by an older minicloze is migrated to the current schema the first time it's read,
+// one version at a time; one written by a newer minicloze is left alone
+
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::grading::Grade;
+use crate::paths::{data_file, lock_data};
+use crate::sentence::convert_error;
+
+pub const SCHEMA: u32 = 1;
+
+// MIGRATIONS[n] turns a record of schema n + 1 into one of schema n + 2
+const MIGRATIONS: [fn(&mut Value); SCHEMA as usize - 1] = [];
+
+#[derive(Deserialize, Serialize)]
+struct Header {
+    schema: u32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    // tatoeba id of the sentence the word was blanked in
+    pub sentence: i32,
+    // tatoeba codes
+    pub language: String,
+    pub native: String,
+    // whether the blank was in the native sentence
+    pub inverse: bool,
+    pub word: String,
+    pub guess: String,
+    pub grade: Grade,
+    // milliseconds from the prompt being shown to it being answered, None for prompts shown
+    // before a session was restored
+    pub response_ms: Option<u64>,
+    // seconds since the unix epoch
+    pub timestamp: u64,
+}
+
+impl Record {
+    pub fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs())
+    }
+}
+
+fn path() -> Result<PathBuf, String> {
+    data_file("history", "answers.jsonl")
+}
+
+// adds a record to the end of the log, starting the log if there isn't one
+pub fn append(record: &Record) -> Result<(), String> {
+    let path = path()?;
+    let _lock = lock_data();
+
+    let schema = schema_of(&path)?;
+
+    // appending current records to an older log would mix schemas
+    if schema.is_some_and(|schema| schema != SCHEMA) {
+        read(&path)?;
+    }
+
+    let new = schema.is_none();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Couldn't open {}: {e}", path.display()))?;
+
+    let mut lines = String::new();
+    if new {
+        lines += &header()?;
+    }
+    lines += &serde_json::to_string(record).map_err(convert_error)?;
+    lines.push('\n');
+
+    file.write_all(lines.as_bytes()).map_err(|e| e.to_string())
+}
+
+// every record, oldest first
+pub fn load() -> Result<Vec<Record>, String> {
+    let path = path()?;
+    let _lock = lock_data();
+
+    read(&path)
+}
+
+// the log's records, migrating it if it's older
+fn read(path: &Path) -> Result<Vec<Record>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let (schema, records) = parse(&text)?;
+
+    if schema < SCHEMA {
+        write(path, &records)?;
+    }
+
+    Ok(records)
+}
+
+// the records for one language
+pub fn load_language(language: &str) -> Result<Vec<Record>, String> {
+    Ok(load()?
+        .into_iter()
+        .filter(|record| record.language == language)
+        .collect())
+}
+
+// copies the log to a file, in the current schema. returns how many records it has
+pub fn backup(to: &Path) -> Result<usize, String> {
+    let records = load()?;
+    write(to, &records)?;
+
+    Ok(records.len())
+}
+
+// replaces the log with a backup, which is migrated if it's older. the log being replaced is
+// kept next to it as answers.jsonl.bak. returns how many records the backup has
+pub fn restore(from: &Path) -> Result<usize, String> {
+    let text =
+        fs::read_to_string(from).map_err(|e| format!("Couldn't read {}: {e}", from.display()))?;
+    let (_, records) = parse(&text)?;
+
+    let path = path()?;
+    let _lock = lock_data();
+
+    if path.exists() {
+        fs::copy(&path, path.with_extension("jsonl.bak")).map_err(|e| e.to_string())?;
+    }
+    write(&path, &records)?;
+
+    Ok(records.len())
+}
+
+// the log's schema, None if it hasn't been started
+fn schema_of(path: &Path) -> Result<Option<u32>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut first = String::new();
+    BufReader::new(file)
+        .read_line(&mut first)
+        .map_err(|e| e.to_string())?;
+
+    if first.trim().is_empty() {
+        return Ok(None);
+    }
+
+    let header: Header = serde_json::from_str(&first).map_err(convert_error)?;
+    Ok(Some(header.schema))
+}
+
+// the log's schema and its records, migrated to the current schema
+fn parse(text: &str) -> Result<(u32, Vec<Record>), String> {
+    parse_with(text, SCHEMA, &MIGRATIONS)
+}
+
+// parse, for a current schema and its migrations
+fn parse_with(
+    text: &str,
+    current: u32,
+    migrations: &[fn(&mut Value)],
+) -> Result<(u32, Vec<Record>), String> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+
+    let Some(first) = lines.next() else {
+        return Ok((current, Vec::new()));
+    };
+    let header: Header = serde_json::from_str(first)
+        .map_err(|_| String::from("This isn't a minicloze answer history"))?;
+
+    if header.schema > current {
+        return Err(format!(
+            "This answer history is from a newer minicloze (schema {}, this one reads up to {current})",
+            header.schema
+        ));
+    }
+    if header.schema == 0 {
+        return Err(String::from("This answer history has no schema version"));
+    }
+
+    let records = lines
+        .enumerate()
+        .map(|(number, line)| {
+            let mut record: Value = serde_json::from_str(line).map_err(convert_error)?;
+
+            for migration in &migrations[header.schema as usize - 1..] {
+                migration(&mut record);
+            }
+
+            // the header is line 1
+            serde_json::from_value(record).map_err(|e| format!("Line {}: {e}", number + 2))
+        })
+        .collect::<Result<_, String>>()?;
+
+    Ok((header.schema, records))
+}
+
+fn header() -> Result<String, String> {
+    let header = serde_json::to_string(&Header { schema: SCHEMA }).map_err(convert_error)?;
+    Ok(header + "\n")
+}
+
+fn write(path: &Path, records: &[Record]) -> Result<(), String> {
+    let mut text = header()?;
+
+    for record in records {
+        text += &serde_json::to_string(record).map_err(convert_error)?;
+        text.push('\n');
+    }
+
+    fs::write(path, text).map_err(|e| format!("Couldn't write {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::paths::isolate;
+
+    fn record(language: &str, word: &str) -> Record {
+        Record {
+            sentence: 1,
+            language: language.to_string(),
+            native: String::from("eng"),
+            inverse: false,
+            word: word.to_string(),
+            guess: word.to_string(),
+            grade: Grade::Correct,
+            response_ms: Some(1500),
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn restores_a_backup() {
+        isolate();
+        // other tests answer in other languages, so only these records are looked at
+        let mine = |records: Vec<Record>| -> Vec<Record> {
+            records
+                .into_iter()
+                .filter(|record| record.language == "fin")
+                .collect()
+        };
+
+        append(&record("fin", "kissa")).unwrap();
+        append(&record("fin", "koira")).unwrap();
+        let copy = data_file("history", "fin-backup.jsonl").unwrap();
+        assert!(backup(&copy).unwrap() >= 2);
+
+        append(&record("fin", "lintu")).unwrap();
+        assert_eq!(mine(load().unwrap()).len(), 3);
+
+        assert!(restore(&copy).unwrap() >= 2);
+        let words: Vec<String> = mine(load().unwrap())
+            .into_iter()
+            .map(|record| record.word)
+            .collect();
+        assert_eq!(words, ["kissa", "koira"]);
+
+        // the log it replaced is kept
+        let replaced = fs::read_to_string(path().unwrap().with_extension("jsonl.bak")).unwrap();
+        assert_eq!(mine(parse(&replaced).unwrap().1).len(), 3);
+    }
+
+    // a made up schema 2, which renamed schema 1's "typed" to "guess"
+    fn rename_typed(record: &mut Value) {
+        if let Some(typed) = record.as_object_mut().and_then(|r| r.remove("typed")) {
+            record["guess"] = typed;
+        }
+    }
+
+    #[test]
+    fn migrates_older_logs() {
+        let mut old = serde_json::to_value(record("fin", "kissa")).unwrap();
+        let guess = old.as_object_mut().unwrap().remove("guess").unwrap();
+        old["typed"] = guess;
+        let text = format!("{{\"schema\": 1}}\n{old}\n\n");
+
+        let (schema, records) = parse_with(&text, 2, &[rename_typed]).unwrap();
+        assert_eq!(schema, 1);
+        assert_eq!(records, [record("fin", "kissa")]);
+
+        // logs of the current schema are read as they are
+        let current = format!(
+            "{}{}\n",
+            header().unwrap(),
+            serde_json::to_string(&record("fin", "koira")).unwrap()
+        );
+        assert_eq!(
+            parse(&current).unwrap(),
+            (SCHEMA, vec![record("fin", "koira")])
+        );
+        assert_eq!(parse("").unwrap(), (SCHEMA, Vec::new()));
+    }
+
+    #[test]
+    fn refuses_logs_it_cant_read() {
+        let newer = format!("{{\"schema\": {}}}\n", SCHEMA + 1);
+        assert!(parse(&newer).unwrap_err().contains("newer minicloze"));
+        assert!(parse("{\"schema\": 0}")
+            .unwrap_err()
+            .contains("no schema version"));
+        assert!(parse("{\"sentence\": 1}").is_err());
+        assert!(parse("hello").is_err());
+
+        let broken = format!("{}{{\"sentence\": 1}}\n", header().unwrap());
+        assert!(parse(&broken).unwrap_err().starts_with("Line 2:"));
+    }
+}
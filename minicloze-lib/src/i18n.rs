@@ -0,0 +1,185 @@
+// the text frontends show, from the Fluent (https://projectfluent.org) catalogs in i18n/, one per
+// ISO 639-1 code. the text is in the native language where there's a catalog for it, and in
+// english otherwise. messages a catalog doesn't have yet are shown in english too. the library
+// itself only hands out Messages, which frontends render, so feedback like a mistake's
+// description is translated along with everything else
+
+use std::sync::OnceLock;
+
+use fluent_bundle::{concurrent::FluentBundle, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+use crate::langs::Language;
+
+pub use fluent_bundle::FluentArgs;
+
+const CATALOGS: [(&str, &str); 4] = [
+    ("en", include_str!("../i18n/en.ftl")),
+    ("de", include_str!("../i18n/de.ftl")),
+    ("es", include_str!("../i18n/es.ftl")),
+    ("fr", include_str!("../i18n/fr.ftl")),
+];
+
+struct Catalog {
+    messages: FluentBundle<FluentResource>,
+    english: FluentBundle<FluentResource>,
+}
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+// a message id in the catalogs and what to fill into it, e.g. "mistake-accent-missing"
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub id: String,
+    pub args: Vec<(String, Arg)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Arg {
+    // shown as it is, e.g. a lemma
+    Text(String),
+    // translated too, e.g. a part of speech
+    Message(Message),
+    // shown one after another with spaces between, e.g. "1pl present"
+    List(Vec<Arg>),
+}
+
+impl Message {
+    pub fn new(id: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn arg(mut self, name: &str, arg: Arg) -> Message {
+        self.args.push((name.to_string(), arg));
+        self
+    }
+}
+
+// picks the catalog for a tatoeba code. only the first call counts, later ones are ignored
+pub fn init(native: &str) {
+    let iso1 = Language::from_code(native).and_then(|language| language.iso1);
+    let (code, source) = CATALOGS
+        .iter()
+        .find(|(code, _)| Some(*code) == iso1)
+        .unwrap_or(&CATALOGS[0]);
+
+    let _ = CATALOG.set(Catalog {
+        messages: bundle(code, source),
+        english: bundle(CATALOGS[0].0, CATALOGS[0].1),
+    });
+}
+
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    let catalog = CATALOG.get_or_init(|| Catalog {
+        messages: bundle(CATALOGS[0].0, CATALOGS[0].1),
+        english: bundle(CATALOGS[0].0, CATALOGS[0].1),
+    });
+
+    [&catalog.messages, &catalog.english]
+        .into_iter()
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(id)?.value()?;
+            let mut errors = Vec::new();
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .to_string(),
+            )
+        })
+        // a typo in an id shouldn't take the game down
+        .unwrap_or_else(|| id.to_string())
+}
+
+pub fn render(message: &Message) -> String {
+    let mut args = FluentArgs::new();
+
+    for (name, arg) in &message.args {
+        args.set(name.as_str(), render_arg(arg));
+    }
+
+    self::message(&message.id, Some(&args))
+}
+
+fn render_arg(arg: &Arg) -> String {
+    match arg {
+        Arg::Text(text) => text.clone(),
+        Arg::Message(message) => render(message),
+        Arg::List(args) => args.iter().map(render_arg).collect::<Vec<_>>().join(" "),
+    }
+}
+
+// the catalogs are compiled in, so a broken one is a bug rather than something to report
+fn bundle(code: &str, source: &str) -> FluentBundle<FluentResource> {
+    let locale: LanguageIdentifier = code.parse().expect("catalog codes are valid");
+    let resource = FluentResource::try_new(source.to_string())
+        .unwrap_or_else(|_| panic!("i18n/{code}.ftl doesn't parse"));
+
+    let mut bundle = FluentBundle::new_concurrent(vec![locale]);
+    // bidi isolates are up to the caller
+    bundle.set_use_isolating(false);
+    bundle
+        .add_resource(resource)
+        .unwrap_or_else(|_| panic!("i18n/{code}.ftl has a message twice"));
+    bundle
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use fluent_bundle::FluentResource;
+    use fluent_syntax::ast::Entry;
+
+    use super::*;
+
+    fn ids(code: &str, source: &str) -> BTreeSet<String> {
+        let resource = FluentResource::try_new(source.to_string())
+            .unwrap_or_else(|(_, errors)| panic!("i18n/{code}.ftl doesn't parse: {errors:?}"));
+
+        resource
+            .entries()
+            .filter_map(|entry| match entry {
+                Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn catalogs_have_the_same_messages() {
+        let english = ids(CATALOGS[0].0, CATALOGS[0].1);
+
+        for (code, source) in &CATALOGS[1..] {
+            let catalog = ids(code, source);
+            let missing: Vec<_> = english.difference(&catalog).collect();
+            let extra: Vec<_> = catalog.difference(&english).collect();
+
+            assert!(missing.is_empty(), "i18n/{code}.ftl is missing {missing:?}");
+            assert!(extra.is_empty(), "i18n/{code}.ftl has unknown {extra:?}");
+        }
+    }
+
+    #[test]
+    fn renders_nested_messages() {
+        let expected = Arg::List(vec![
+            Arg::Text("1pl".to_string()),
+            Arg::Message(Message::new("feature-present")),
+        ]);
+        let message = Message::new("inflection-spelling")
+            .arg(
+                "part_of_speech",
+                Arg::Message(Message::new("part-of-speech-verb")),
+            )
+            .arg("expected", expected);
+
+        assert_eq!(
+            render(&message),
+            "right verb, wrong spelling: 1pl present expected"
+        );
+        // an unknown id is shown as it is
+        assert_eq!(render(&Message::new("no-such-message")), "no-such-message");
+    }
+}
@@ -0,0 +1,202 @@
+// the words the learner knows in each language, kept in known/<language>.json in the data dir.
+// every word answered right is added, and word lists can be imported, e.g. notes exported from
+// anki. rounds prefer sentences where every word but the blank is known, so the sentence around
+// the blank is comprehensible input
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::langs::is_spaced;
+use crate::morph::WordProgress;
+use crate::paths::data_file;
+use crate::sentence::{convert_error, remove_punctuation, Prompt, Sentence};
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct KnownWords {
+    // lowercased, without punctuation
+    pub words: BTreeSet<String>,
+}
+
+fn normalise(word: &str) -> String {
+    remove_punctuation(word).trim().to_lowercase()
+}
+
+impl KnownWords {
+    // a language without a file yet starts with the words answered right before there was one
+    pub fn load(language: &str) -> Result<KnownWords, String> {
+        let path = data_file("known", &format!("{language}.json"))?;
+
+        if !path.exists() {
+            let progress = WordProgress::load(language)?;
+
+            return Ok(KnownWords {
+                words: progress
+                    .forms
+                    .into_iter()
+                    .filter(|(_, score)| score.correct > 0)
+                    .map(|(form, _)| form)
+                    .collect(),
+            });
+        }
+
+        let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(convert_error)
+    }
+
+    pub fn save(&self, language: &str) -> Result<(), String> {
+        let path = data_file("known", &format!("{language}.json"))?;
+        let json = serde_json::to_string(self).map_err(convert_error)?;
+
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    // adds the words in a plain list, one per line, or in anki notes exported as plain text,
+    // where the first field of each note is taken. returns how many were new
+    pub fn import(language: &str, source: &Path) -> Result<usize, String> {
+        let text = fs::read_to_string(source)
+            .map_err(|e| format!("Couldn't read {}: {e}", source.display()))?;
+        let mut known = KnownWords::load(language)?;
+        let before = known.words.len();
+
+        // anki's header lines start with #, e.g. "#separator:tab"
+        for line in text.lines().filter(|line| !line.starts_with('#')) {
+            let field = line.split('\t').next().unwrap_or_default();
+
+            for word in strip_html(field).split_whitespace() {
+                known.add(word);
+            }
+        }
+
+        known.save(language)?;
+        Ok(known.words.len() - before)
+    }
+
+    pub fn add(&mut self, word: &str) {
+        let word = normalise(word);
+
+        if !word.is_empty() {
+            self.words.insert(word);
+        }
+    }
+
+    // words without letters, e.g. numbers, are known
+    pub fn knows(&self, word: &str) -> bool {
+        let word = normalise(word);
+        !word.chars().any(char::is_alphabetic) || self.words.contains(&word)
+    }
+
+    // the words of the sentence in the language being learnt which aren't known, leaving out
+    // the blank unless it's in the native sentence
+    pub fn unknown_around(
+        &self,
+        sentence: &Sentence,
+        prompt: &Prompt,
+        language: &str,
+        inverse: bool,
+    ) -> usize {
+        if !is_spaced(language) {
+            return self.unknown_characters(sentence, prompt, inverse);
+        }
+
+        let mut unknown = sentence
+            .as_words(language, false)
+            .iter()
+            .filter(|word| !self.knows(word))
+            .count();
+
+        if !inverse && !self.knows(&prompt.word) {
+            unknown = unknown.saturating_sub(1);
+        }
+
+        unknown
+    }
+
+    // without spaces, the sentence can't be split into words to look up. instead the known
+    // words are found in it, e.g. imported ones in japanese or chinese, and the letters none of
+    // them cover are counted
+    fn unknown_characters(&self, sentence: &Sentence, prompt: &Prompt, inverse: bool) -> usize {
+        let text = if inverse {
+            sentence
+                .get_translation()
+                .map_or_else(String::new, |t| normalise(&t.text))
+        } else {
+            // apart, so no word is found across the blank
+            format!(
+                "{}\n{}",
+                normalise(&prompt.first_half),
+                normalise(&prompt.second_half)
+            )
+        };
+
+        let mut covered = vec![false; text.len()];
+        for word in self.words.iter().filter(|word| !word.is_empty()) {
+            for (start, _) in text.match_indices(word.as_str()) {
+                covered[start..start + word.len()].fill(true);
+            }
+        }
+
+        text.char_indices()
+            .filter(|(i, c)| c.is_alphabetic() && !covered[*i])
+            .count()
+    }
+}
+
+// anki exports fields as html unless told not to
+fn strip_html(field: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in field.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                // tags like <br> separate words
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text.replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sentence::Translation;
+
+    #[test]
+    fn finds_known_words_in_unspaced_sentences() {
+        let known = KnownWords {
+            words: BTreeSet::from([String::from("猫"), String::from("好き")]),
+        };
+        let sentence = Sentence {
+            id: 1,
+            text: String::from("I like cats."),
+            translations: vec![Translation {
+                id: 2,
+                text: String::from("猫が好きです。"),
+                transcriptions: Vec::new(),
+            }],
+        };
+        let prompt = Prompt {
+            first_half: String::from("猫"),
+            word: String::from("が"),
+            second_half: String::from("好きです。"),
+        };
+
+        // で and す, the blank is left out
+        assert_eq!(known.unknown_around(&sentence, &prompt, "jpn", false), 2);
+        // が too, when the blank is in the english sentence
+        assert_eq!(known.unknown_around(&sentence, &prompt, "jpn", true), 3);
+    }
+}
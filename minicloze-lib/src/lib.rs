@@ -1,35 +1,38 @@
+pub mod alignment;
+pub mod cache;
+pub mod config;
+pub mod definitions;
+pub mod dictionary;
+pub mod grading;
+pub mod history;
+pub mod i18n;
+pub mod known;
 pub mod langs;
+pub mod localdict;
+pub mod missed;
+pub mod morph;
+pub mod optimize;
+pub mod paths;
+pub mod resolve;
 pub mod sentence;
+pub mod session;
+pub mod srs;
+pub mod stats;
 
 // handles wiktionary lookup
 pub mod wiktionary {
+    use crate::dictionary::{DictionaryProvider, Wiktionary};
+
     pub fn wiktionary_try_open(lookup: String, language: &str) {
         webbrowser::open(&generate_url(&lookup, language)).unwrap();
     }
 
+    // english wiktionary, other providers are in crate::dictionary
     pub fn generate_url(lookup: &str, language: &str) -> String {
-        let lang_codes = crate::langs::propagate();
-
-        let mut full_language = String::new();
-        // gets key from value
-        for pair in lang_codes {
-            if pair.1 == language {
-                full_language = pair.0.to_string();
-            }
+        Wiktionary {
+            edition: String::from("en"),
         }
-
-        let titlecase_language = format!(
-            "{}{}",
-            full_language[..1].to_uppercase(),
-            &full_language[1..]
-        );
-
-        [
-            "https://en.wiktionary.org/wiki/",
-            lookup.trim(),
-            "#",
-            titlecase_language.as_str(),
-        ]
-        .join("")
+        .url(lookup, language)
+        .unwrap_or_default()
     }
 }
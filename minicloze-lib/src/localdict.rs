@@ -0,0 +1,419 @@
+// readers for bilingual dictionaries in StarDict (.ifo/.idx/.dict[.dz]) and dictd
+// (.index/.dict[.dz]) format, e.g. the ones from https://freedict.org. they're kept in the data
+// dir under dictionaries/<tatoeba code>/, and every dictionary found there is loaded
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+
+use crate::definitions::Definition;
+use crate::dictionary::DictionaryProvider;
+use crate::paths::data_file;
+
+// the dictd index encodes numbers in base64 with this alphabet
+const DICTD_DIGITS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// a dictionary fully read into memory. both formats boil down to an index of byte ranges into
+// one big text file
+pub struct LocalDictionary {
+    pub name: String,
+    // lowercased headword -> (offset, size) of each article in data
+    index: HashMap<String, Vec<(usize, usize)>>,
+    data: Vec<u8>,
+    // stardict's sametypesequence, empty if it has none. None for dictd
+    types: Option<String>,
+}
+
+impl LocalDictionary {
+    // path is the .ifo file
+    pub fn open_stardict(path: &Path) -> Result<LocalDictionary, String> {
+        let ifo = read_text(path)?;
+
+        if !ifo.starts_with("StarDict's dict ifo file") {
+            return Err(format!("{} isn't a StarDict .ifo file", path.display()));
+        }
+
+        Ok(LocalDictionary::stardict(
+            &ifo,
+            &read_maybe_gzipped(&path.with_extension("idx"))?,
+            read_maybe_gzipped(&path.with_extension("dict"))?,
+            &file_stem(path),
+        ))
+    }
+
+    // path is the .index file
+    pub fn open_dictd(path: &Path) -> Result<LocalDictionary, String> {
+        Ok(LocalDictionary::dictd(
+            &read_text(path)?,
+            read_maybe_gzipped(&path.with_extension("dict"))?,
+            &file_stem(path),
+        ))
+    }
+
+    // a StarDict dictionary from its files' contents, named after the file if the .ifo has no
+    // bookname
+    fn stardict(ifo: &str, idx: &[u8], data: Vec<u8>, name: &str) -> LocalDictionary {
+        let option = |key: &str| {
+            ifo.lines()
+                .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+                .map(|value| value.trim().to_string())
+        };
+
+        let offset_bytes = if option("idxoffsetbits").as_deref() == Some("64") {
+            8
+        } else {
+            4
+        };
+
+        let mut index: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+        let mut rest = idx;
+
+        // each entry is a nul-terminated headword, then a big-endian offset and size
+        while let Some(nul) = rest.iter().position(|b| *b == 0) {
+            let word = String::from_utf8_lossy(&rest[..nul]).to_lowercase();
+            rest = &rest[nul + 1..];
+
+            if rest.len() < offset_bytes + 4 {
+                break;
+            }
+
+            let offset = be_number(&rest[..offset_bytes]);
+            let size = be_number(&rest[offset_bytes..offset_bytes + 4]);
+            rest = &rest[offset_bytes + 4..];
+
+            index.entry(word).or_default().push((offset, size));
+        }
+
+        LocalDictionary {
+            name: option("bookname").unwrap_or_else(|| name.to_string()),
+            index,
+            data,
+            types: Some(option("sametypesequence").unwrap_or_default()),
+        }
+    }
+
+    // a dictd dictionary from its files' contents
+    fn dictd(tsv: &str, data: Vec<u8>, name: &str) -> LocalDictionary {
+        let mut index: HashMap<String, Vec<(usize, usize)>> = HashMap::new();
+
+        for line in tsv.lines() {
+            let mut columns = line.split('\t');
+
+            let (Some(word), Some(offset), Some(size)) =
+                (columns.next(), columns.next(), columns.next())
+            else {
+                continue;
+            };
+
+            // dictd keeps its own metadata under headwords starting with 00-database
+            if word.starts_with("00-database") || word.starts_with("00database") {
+                continue;
+            }
+
+            if let (Some(offset), Some(size)) = (dictd_number(offset), dictd_number(size)) {
+                index
+                    .entry(word.to_lowercase())
+                    .or_default()
+                    .push((offset, size));
+            }
+        }
+
+        LocalDictionary {
+            name: name.to_string(),
+            index,
+            data,
+            types: None,
+        }
+    }
+
+    // every dictionary in dictionaries/<language>/, skipping any that fail to open
+    pub fn load_all(language: &str) -> Vec<LocalDictionary> {
+        let Ok(dir) = data_file("dictionaries", language) else {
+            return Vec::new();
+        };
+        let Ok(files) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut paths: Vec<PathBuf> = files.filter_map(|f| Some(f.ok()?.path())).collect();
+        paths.sort();
+
+        paths
+            .iter()
+            .filter_map(|path| match path.extension()?.to_str()? {
+                "ifo" => LocalDictionary::open_stardict(path).ok(),
+                "index" => LocalDictionary::open_dictd(path).ok(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // the article text for each entry of the word
+    pub fn articles(&self, word: &str) -> Vec<String> {
+        let Some(ranges) = self.index.get(&word.trim().to_lowercase()) else {
+            return Vec::new();
+        };
+
+        // a range past the end of the data, or overflowing, is a corrupt entry and skipped
+        ranges
+            .iter()
+            .filter_map(|(offset, size)| self.data.get(*offset..offset.checked_add(*size)?))
+            .map(|bytes| match &self.types {
+                Some(types) => stardict_text(bytes, types),
+                None => dictd_text(bytes, word),
+            })
+            .filter(|text| !text.is_empty())
+            .collect()
+    }
+}
+
+impl DictionaryProvider for LocalDictionary {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn url(&self, _word: &str, _language: &str) -> Option<String> {
+        None
+    }
+
+    fn lookup(&self, word: &str, _language: &str) -> Vec<Definition> {
+        self.articles(word)
+            .into_iter()
+            .map(|text| Definition {
+                pos: String::new(),
+                gender: None,
+                ipa: None,
+                glosses: vec![text],
+            })
+            .collect()
+    }
+}
+
+fn read_text(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Couldn't read {}: {e}", path.display()))
+}
+
+// reads path, or path.dz / path.gz if that's what exists. dictzip files are ordinary gzip
+// files, the random access table can be ignored when reading the whole thing
+fn read_maybe_gzipped(path: &Path) -> Result<Vec<u8>, String> {
+    if path.exists() {
+        return fs::read(path).map_err(|e| format!("Couldn't read {}: {e}", path.display()));
+    }
+
+    for extension in ["dz", "gz"] {
+        let compressed = PathBuf::from(format!("{}.{extension}", path.display()));
+
+        if compressed.exists() {
+            let file = fs::File::open(&compressed).map_err(|e| e.to_string())?;
+            let mut data = Vec::new();
+
+            GzDecoder::new(file)
+                .read_to_end(&mut data)
+                .map_err(|e| format!("Couldn't decompress {}: {e}", compressed.display()))?;
+            return Ok(data);
+        }
+    }
+
+    Err(format!("{} is missing", path.display()))
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn be_number(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0, |n, b| (n << 8) | *b as usize)
+}
+
+fn dictd_number(digits: &str) -> Option<usize> {
+    digits
+        .chars()
+        .try_fold(0, |n, c| DICTD_DIGITS.find(c).map(|digit| (n << 6) | digit))
+}
+
+// an article is a sequence of typed fields: lowercase types are nul-terminated text, uppercase
+// ones binary data prefixed by their size. without a sametypesequence each field starts with its
+// type, with one the types are left out and so is the last field's terminator or size.
+// only the textual types are kept
+fn stardict_text(bytes: &[u8], types: &str) -> String {
+    let mut fields = Vec::new();
+    let mut rest = bytes;
+    let mut sequence = types.chars().peekable();
+
+    while !rest.is_empty() {
+        let kind = if types.is_empty() {
+            let kind = rest[0] as char;
+            rest = &rest[1..];
+            kind
+        } else {
+            match sequence.next() {
+                Some(kind) => kind,
+                None => break,
+            }
+        };
+        let last = !types.is_empty() && sequence.peek().is_none();
+
+        let field = if kind.is_ascii_uppercase() {
+            // sounds, pictures and the like
+            if last || rest.len() < 4 {
+                break;
+            }
+            let size = be_number(&rest[..4]);
+            rest = size
+                .checked_add(4)
+                .and_then(|end| rest.get(end..))
+                .unwrap_or(&[]);
+            continue;
+        } else if last {
+            std::mem::take(&mut rest)
+        } else {
+            let end = rest.iter().position(|b| *b == 0).unwrap_or(rest.len());
+            let field = &rest[..end];
+            rest = rest.get(end + 1..).unwrap_or(&[]);
+            field
+        };
+
+        let text = String::from_utf8_lossy(field);
+        match kind {
+            'm' | 't' | 'y' | 'l' => fields.push(text.trim().to_string()),
+            'h' | 'g' | 'x' => fields.push(strip_markup(&text)),
+            _ => (),
+        }
+    }
+
+    fields.retain(|f| !f.is_empty());
+    fields.join(" ")
+}
+
+// dictd articles usually repeat the headword on their first line
+fn dictd_text(bytes: &[u8], word: &str) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    let first = lines.next().unwrap_or_default();
+    let mut kept: Vec<&str> = Vec::new();
+
+    if !first
+        .to_lowercase()
+        .starts_with(&word.trim().to_lowercase())
+    {
+        kept.push(first);
+    }
+    kept.extend(lines);
+
+    kept.join("; ")
+}
+
+fn strip_markup(text: &str) -> String {
+    let mut plain = String::new();
+    let mut in_tag = false;
+
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => {
+                in_tag = false;
+                plain.push(' ');
+            }
+            _ if !in_tag => plain.push(c),
+            _ => (),
+        }
+    }
+
+    plain.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IFO: &str = "StarDict's dict ifo file\nversion=2.4.2\nbookname=English-French\n";
+
+    // a .idx entry with 32 bit offsets
+    fn entry(word: &str, offset: u32, size: u32) -> Vec<u8> {
+        let mut entry = word.as_bytes().to_vec();
+        entry.push(0);
+        entry.extend(offset.to_be_bytes());
+        entry.extend(size.to_be_bytes());
+        entry
+    }
+
+    #[test]
+    fn reads_stardict() {
+        let ifo = format!("{IFO}sametypesequence=m\n");
+        let data = b"chatfelinmaison".to_vec();
+        let idx = [entry("Cat", 0, 4), entry("cat", 4, 5), entry("house", 9, 6)].concat();
+
+        let dictionary = LocalDictionary::stardict(&ifo, &idx, data, "file");
+        assert_eq!(dictionary.name, "English-French");
+        assert_eq!(dictionary.articles("CAT"), ["chat", "felin"]);
+        assert_eq!(dictionary.articles("house"), ["maison"]);
+        assert!(dictionary.articles("dog").is_empty());
+    }
+
+    #[test]
+    fn reads_typed_stardict_fields() {
+        // text, a sound to skip, then html
+        let data = [
+            &b"mchat\0"[..],
+            b"W",
+            &3u32.to_be_bytes(),
+            b"\x01\x02\x03",
+            b"h<b>felin</b> <i>m</i>\0",
+        ]
+        .concat();
+        let idx = entry("cat", 0, data.len() as u32);
+
+        let dictionary = LocalDictionary::stardict(IFO, &idx, data, "file");
+        assert_eq!(dictionary.articles("cat"), ["chat felin m"]);
+    }
+
+    #[test]
+    fn skips_broken_stardict_entries() {
+        let ifo = format!("{IFO}sametypesequence=m\n");
+        let data = b"chat".to_vec();
+
+        // the last entry is cut off after its offset
+        let mut idx = [entry("cat", 0, 4), entry("dog", 0, 4)].concat();
+        idx.truncate(idx.len() - 2);
+        let dictionary = LocalDictionary::stardict(&ifo, &idx, data.clone(), "file");
+        assert_eq!(dictionary.articles("cat"), ["chat"]);
+        assert!(dictionary.articles("dog").is_empty());
+
+        // offsets past the end of the data, and one overflowing with its size
+        let ifo = format!("{ifo}idxoffsetbits=64\n");
+        let far = [&b"far\0"[..], &100u64.to_be_bytes(), &4u32.to_be_bytes()].concat();
+        let huge = [&b"huge\0"[..], &u64::MAX.to_be_bytes(), &4u32.to_be_bytes()].concat();
+        let dictionary = LocalDictionary::stardict(&ifo, &[far, huge].concat(), data, "file");
+        assert!(dictionary.articles("far").is_empty());
+        assert!(dictionary.articles("huge").is_empty());
+    }
+
+    #[test]
+    fn reads_dictd() {
+        let data = b"cat\n chat\nHouse\n maison\n".to_vec();
+        // offsets and sizes in base64: K is 10, O is 14 and BA is 64
+        let index = "00-databaseinfo\tA\tK
+cat\tA\tK
+house\tK\tO
+far\tBA\tE
+bad\t!\tE
+short\tA
+";
+
+        let dictionary = LocalDictionary::dictd(index, data, "freedict-eng-fra");
+        assert_eq!(dictionary.name, "freedict-eng-fra");
+        // the repeated headword is left out
+        assert_eq!(dictionary.articles("cat"), ["chat"]);
+        assert_eq!(dictionary.articles("House"), ["maison"]);
+        assert!(dictionary.articles("00-databaseinfo").is_empty());
+        assert!(dictionary.articles("far").is_empty());
+        assert!(dictionary.articles("bad").is_empty());
+        assert!(dictionary.articles("short").is_empty());
+    }
+}
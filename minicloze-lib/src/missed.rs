@@ -0,0 +1,98 @@
+// the prompts answered wrongly or nearly, for review rounds, until a review round gets them
+// right. each is kept with its sentence and the exact blank, so it can be played again just as
+// it was, offline. every pair of languages has its own file, missed/<native>-<language>.json in
+// the data dir, like the cache. inverse mode isn't kept, since the blank is in the native
+// language
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::grading::{Grade, Mistake};
+use crate::paths::{data_file, lock_data};
+use crate::sentence::{convert_error, Prompt, Sentence};
+use crate::stats::day;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Miss {
+    pub sentence: Sentence,
+    pub prompt: Prompt,
+    pub guess: String,
+    // Close or Wrong
+    pub grade: Grade,
+    pub mistake: Option<Mistake>,
+    // seconds since the unix epoch
+    pub timestamp: u64,
+}
+
+// which misses a review round picks from. days are counted as in stats.rs, and both ends are
+// included
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct MissFilter {
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub grade: Option<Grade>,
+    pub mistake: Option<Mistake>,
+}
+
+impl MissFilter {
+    pub fn matches(&self, miss: &Miss) -> bool {
+        let day = day(miss.timestamp);
+
+        self.since.is_none_or(|since| day >= since)
+            && self.until.is_none_or(|until| day <= until)
+            && self.grade.is_none_or(|grade| miss.grade == grade)
+            && self
+                .mistake
+                .is_none_or(|mistake| miss.mistake == Some(mistake))
+    }
+}
+
+fn file_name(language: &str, native: &str) -> String {
+    format!("{native}-{language}.json")
+}
+
+// oldest first
+pub fn load(language: &str, native: &str) -> Result<Vec<Miss>, String> {
+    let path = data_file("missed", &file_name(language, native))?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(convert_error)
+}
+
+// adds a miss, replacing an earlier one of the same blank in the same sentence
+pub fn record(language: &str, native: &str, miss: Miss) -> Result<(), String> {
+    let _lock = lock_data();
+    let mut missed = load(language, native)?;
+
+    missed.retain(|m| {
+        m.sentence.id != miss.sentence.id
+            || m.prompt.first_half != miss.prompt.first_half
+            || m.prompt.word != miss.prompt.word
+    });
+    missed.push(miss);
+
+    let json = serde_json::to_string(&missed).map_err(convert_error)?;
+    fs::write(data_file("missed", &file_name(language, native))?, json).map_err(|e| e.to_string())
+}
+
+// forgets the misses of a word in a sentence, once it's been answered right
+pub fn resolve(language: &str, native: &str, sentence: i32, word: &str) -> Result<(), String> {
+    let _lock = lock_data();
+    let mut missed = load(language, native)?;
+    let before = missed.len();
+
+    missed.retain(|m| m.sentence.id != sentence || m.prompt.word != word);
+
+    if missed.len() == before {
+        return Ok(());
+    }
+
+    let json = serde_json::to_string(&missed).map_err(convert_error)?;
+    fs::write(data_file("missed", &file_name(language, native))?, json).map_err(|e| e.to_string())
+}
@@ -0,0 +1,381 @@
+// inflection lookups using UniMorph lexicons, see https://unimorph.github.io
+// each line of a UniMorph file is "lemma<TAB>form<TAB>features", e.g.
+// "manger	mangeons	V;IND;PRS;1;PL". the lexicon for a language is kept in the data dir
+// under unimorph/<tatoeba code>, which matches how UniMorph names its repositories
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{Arg, Message};
+use crate::paths::data_file;
+
+// one reading of a surface form
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Analysis {
+    pub lemma: String,
+    pub features: Vec<String>,
+}
+
+pub struct Lexicon {
+    // lowercased surface form -> every analysis of it
+    forms: HashMap<String, Vec<Analysis>>,
+}
+
+// the guess is another form of the word's lemma
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Inflection {
+    pub lemma: String,
+    pub part_of_speech: String,
+    // e.g. ["person"], the feature dimensions the guess got wrong
+    pub wrong: Vec<String>,
+    // the word's UniMorph features, e.g. ["V", "IND", "PRS", "1", "PL"]
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default)]
+pub struct Score {
+    pub correct: u32,
+    pub total: u32,
+}
+
+// answers tallied per lemma and per surface form, stored per language
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct WordProgress {
+    pub lemmas: BTreeMap<String, Score>,
+    pub forms: BTreeMap<String, Score>,
+}
+
+// dimensions in the order they're described, with the tags belonging to each
+const DIMENSIONS: [(&str, &[&str]); 9] = [
+    ("form", &["NFIN", "V.PTCP", "V.CVB", "V.MSDR"]),
+    ("person", &["1", "2", "3"]),
+    ("number", &["SG", "PL", "DU"]),
+    ("tense", &["PRS", "PST", "FUT"]),
+    ("aspect", &["IPFV", "PFV", "PRF", "PROG"]),
+    ("mood", &["IND", "SBJV", "COND", "IMP"]),
+    ("gender", &["MASC", "FEM", "NEUT"]),
+    (
+        "case",
+        &[
+            "NOM", "ACC", "GEN", "DAT", "INS", "LOC", "ABL", "VOC", "ESS", "TRANS", "PRT", "ALL",
+            "ADE", "INE", "ELA", "ILL",
+        ],
+    ),
+    ("definiteness", &["DEF", "INDF"]),
+];
+
+impl Lexicon {
+    pub fn parse(tsv: &str) -> Lexicon {
+        let mut forms: HashMap<String, Vec<Analysis>> = HashMap::new();
+
+        for line in tsv.lines() {
+            let mut columns = line.split('\t');
+
+            let (Some(lemma), Some(form), Some(features)) =
+                (columns.next(), columns.next(), columns.next())
+            else {
+                continue;
+            };
+
+            forms
+                .entry(form.trim().to_lowercase())
+                .or_default()
+                .push(Analysis {
+                    lemma: lemma.trim().to_string(),
+                    features: features.trim().split(';').map(str::to_string).collect(),
+                });
+        }
+
+        Lexicon { forms }
+    }
+
+    // the imported lexicon for a language, if there is one
+    pub fn load(language: &str) -> Result<Option<Lexicon>, String> {
+        let path = data_file("unimorph", language)?;
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let tsv = fs::read_to_string(&path)
+            .map_err(|e| format!("Couldn't read {}: {e}", path.display()))?;
+        Ok(Some(Lexicon::parse(&tsv)))
+    }
+
+    // copies a UniMorph file into the data dir, returns how many forms it has
+    pub fn import(language: &str, source: &Path) -> Result<usize, String> {
+        let tsv = fs::read_to_string(source)
+            .map_err(|e| format!("Couldn't read {}: {e}", source.display()))?;
+        let lexicon = Lexicon::parse(&tsv);
+
+        if lexicon.forms.is_empty() {
+            return Err(format!(
+                "{} doesn't look like a UniMorph file",
+                source.display()
+            ));
+        }
+
+        fs::write(data_file("unimorph", language)?, tsv).map_err(|e| e.to_string())?;
+        Ok(lexicon.forms.len())
+    }
+
+    pub fn analyse(&self, form: &str) -> &[Analysis] {
+        self.forms
+            .get(&form.trim().to_lowercase())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn lemma_of(&self, form: &str) -> Option<&str> {
+        self.analyse(form).first().map(|a| a.lemma.as_str())
+    }
+
+    // if the guess is a different form of the same lemma as the word, says how it differs
+    pub fn compare(&self, guess: &str, word: &str) -> Option<Inflection> {
+        let mut best: Option<(usize, &Analysis, Vec<String>)> = None;
+
+        for target in self.analyse(word) {
+            for guessed in self.analyse(guess) {
+                if guessed.lemma != target.lemma {
+                    continue;
+                }
+
+                let mut wrong = differing_dimensions(&guessed.features, &target.features);
+
+                // e.g. an infinitive for a finite verb, the other dimensions don't matter
+                if wrong.iter().any(|d| d == "form") {
+                    wrong.truncate(1);
+                }
+
+                // the pair of readings that differ the least explains the guess best
+                if best.as_ref().is_none_or(|(n, _, _)| wrong.len() < *n) {
+                    best = Some((wrong.len(), target, wrong));
+                }
+            }
+        }
+
+        let (_, target, wrong) = best?;
+
+        Some(Inflection {
+            lemma: target.lemma.clone(),
+            part_of_speech: part_of_speech(&target.features).to_string(),
+            wrong,
+            features: target.features.clone(),
+        })
+    }
+}
+
+impl Inflection {
+    // e.g. "right verb, wrong person: 1pl present expected" in english
+    pub fn describe(&self) -> Message {
+        let message = |id: String| Arg::Message(Message::new(&id));
+        let part_of_speech = message(format!("part-of-speech-{}", self.part_of_speech));
+        let expected = describe_features(&self.features);
+
+        if self.wrong.is_empty() {
+            return Message::new("inflection-spelling")
+                .arg("part_of_speech", part_of_speech)
+                .arg("expected", expected);
+        }
+
+        let mut wrong = Vec::new();
+        for (i, dimension) in self.wrong.iter().enumerate() {
+            if i > 0 {
+                wrong.push(message(String::from("and")));
+            }
+            wrong.push(message(format!("dimension-{dimension}")));
+        }
+
+        Message::new("inflection-wrong")
+            .arg("part_of_speech", part_of_speech)
+            .arg("wrong", Arg::List(wrong))
+            .arg("expected", expected)
+    }
+}
+
+impl WordProgress {
+    pub fn load(language: &str) -> Result<WordProgress, String> {
+        let path = data_file("lemmas", &format!("{language}.json"))?;
+
+        if !path.exists() {
+            return Ok(WordProgress::default());
+        }
+
+        let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(crate::sentence::convert_error)
+    }
+
+    pub fn save(&self, language: &str) -> Result<(), String> {
+        let path = data_file("lemmas", &format!("{language}.json"))?;
+        let json = serde_json::to_string(self).map_err(crate::sentence::convert_error)?;
+
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    // lemma is None when there's no lexicon or the word isn't in it
+    pub fn record(&mut self, word: &str, lemma: Option<&str>, correct: bool) {
+        let tally = |score: &mut Score| {
+            score.total += 1;
+            score.correct += u32::from(correct);
+        };
+
+        tally(self.forms.entry(word.trim().to_lowercase()).or_default());
+
+        if let Some(lemma) = lemma {
+            tally(self.lemmas.entry(lemma.to_string()).or_default());
+        }
+    }
+}
+
+fn dimension_of(tag: &str) -> Option<&'static str> {
+    DIMENSIONS
+        .iter()
+        .find(|(_, tags)| tags.contains(&tag))
+        .map(|(name, _)| *name)
+}
+
+fn differing_dimensions(guessed: &[String], target: &[String]) -> Vec<String> {
+    DIMENSIONS
+        .iter()
+        .filter(|(name, _)| {
+            let pick = |features: &[String]| {
+                features
+                    .iter()
+                    .filter(|tag| dimension_of(tag) == Some(name))
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            pick(guessed) != pick(target)
+        })
+        .map(|(name, _)| name.to_string())
+        .collect()
+}
+
+fn part_of_speech(features: &[String]) -> &'static str {
+    for tag in features {
+        match tag.as_str() {
+            "V" | "V.PTCP" | "V.CVB" | "V.MSDR" => return "verb",
+            "N" | "PROPN" => return "noun",
+            "ADJ" => return "adjective",
+            "ADV" => return "adverb",
+            "PRO" => return "pronoun",
+            "DET" => return "determiner",
+            _ => (),
+        }
+    }
+    "word"
+}
+
+// turns "V;IND;PRS;1;PL" into "1pl present" in english
+fn describe_features(features: &[String]) -> Arg {
+    let has = |tag: &str| features.iter().any(|t| t == tag);
+    let mut words = Vec::new();
+
+    let person = ["1", "2", "3"].into_iter().find(|p| has(p));
+    let number = [("SG", "sg"), ("PL", "pl"), ("DU", "du")]
+        .into_iter()
+        .find(|(tag, _)| has(tag))
+        .map(|(_, short)| short);
+
+    // glossing abbreviations, which aren't translated
+    match (person, number) {
+        (Some(person), Some(number)) => words.push(Arg::Text(format!("{person}{number}"))),
+        (Some(person), None) => words.push(Arg::Text(format!("{person}p"))),
+        (None, Some(number)) => words.push(Arg::Text(number.to_string())),
+        (None, None) => (),
+    }
+
+    for tag in features {
+        let feature = match tag.as_str() {
+            "PRS" => "present",
+            "PST" => "past",
+            "FUT" => "future",
+            "IPFV" => "imperfective",
+            "PFV" => "perfective",
+            "PRF" => "perfect",
+            "SBJV" => "subjunctive",
+            "COND" => "conditional",
+            "IMP" => "imperative",
+            "NFIN" => "infinitive",
+            "V.PTCP" => "participle",
+            "V.CVB" => "converb",
+            "MASC" => "masculine",
+            "FEM" => "feminine",
+            "NEUT" => "neuter",
+            "DEF" => "definite",
+            "INDF" => "indefinite",
+            other if dimension_of(other) == Some("case") => {
+                words.push(Arg::Text(other.to_lowercase()));
+                continue;
+            }
+            _ => continue,
+        };
+        words.push(Arg::Message(Message::new(&format!("feature-{feature}"))));
+    }
+
+    if words.is_empty() {
+        Arg::Text(features.join(";"))
+    } else {
+        Arg::List(words)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::i18n::render;
+
+    const FRENCH: &str = "manger\tmangeons\tV;IND;PRS;1;PL
+manger\tmangez\tV;IND;PRS;2;PL
+manger\tmanger\tV;NFIN
+manger\tmangeait\tV;IND;PST;IPFV;3;SG
+parler\tparlons\tV;IND;PRS;1;PL
+not a unimorph line
+
+chat\tChats\tN;MASC;PL
+";
+
+    #[test]
+    fn parses_unimorph_lines() {
+        let lexicon = Lexicon::parse(FRENCH);
+
+        assert_eq!(lexicon.forms.len(), 6);
+        assert_eq!(lexicon.lemma_of("Mangeons "), Some("manger"));
+        // forms are kept lowercased
+        assert_eq!(lexicon.lemma_of("chats"), Some("chat"));
+        assert_eq!(
+            lexicon.analyse("mangeait")[0].features,
+            ["V", "IND", "PST", "IPFV", "3", "SG"]
+        );
+        assert!(lexicon.analyse("not").is_empty());
+    }
+
+    #[test]
+    fn compares_inflections() {
+        let lexicon = Lexicon::parse(FRENCH);
+
+        let person = lexicon.compare("mangez", "mangeons").unwrap();
+        assert_eq!(person.lemma, "manger");
+        assert_eq!(person.part_of_speech, "verb");
+        assert_eq!(person.wrong, ["person"]);
+        assert_eq!(
+            render(&person.describe()),
+            "right verb, wrong person: 1pl present expected"
+        );
+
+        // an infinitive is wrong in form, whatever else differs
+        let form = lexicon.compare("manger", "mangeait").unwrap();
+        assert_eq!(form.wrong, ["form"]);
+        assert_eq!(
+            render(&form.describe()),
+            "right verb, wrong form: 3sg past imperfective expected"
+        );
+
+        // another lemma, or a word the lexicon doesn't know, isn't an inflection
+        assert!(lexicon.compare("parlons", "mangeons").is_none());
+        assert!(lexicon.compare("mangeos", "mangeons").is_none());
+    }
+}
@@ -0,0 +1,259 @@
+// fits FSRS's weights to a language's answer history, on this computer. every card's answers
+// are replayed through FSRS, which predicts how likely each answer was to be remembered, and
+// the weights are moved by gradient descent (Adam, with finite differences for the gradient)
+// to make those predictions better, as measured by log loss. only answers a day or more after
+// the card's previous one are predicted, as in FSRS's own optimizer
+
+use std::collections::BTreeMap;
+
+use crate::history::Record;
+use crate::srs::{Card, Fsrs, Rating};
+
+// answers needed before fitting means anything
+pub const MIN_REVIEWS: usize = 32;
+
+const DAY: f64 = 86_400.0;
+
+// each weight's range, from FSRS-4.5
+const BOUNDS: [(f64, f64); 17] = [
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (0.1, 100.0),
+    (1.0, 10.0),
+    (0.1, 5.0),
+    (0.1, 5.0),
+    (0.0, 0.5),
+    (0.0, 3.0),
+    (0.1, 0.8),
+    (0.01, 2.5),
+    (0.5, 5.0),
+    (0.01, 0.2),
+    (0.01, 0.9),
+    (0.01, 2.0),
+    (0.0, 1.0),
+    (1.0, 6.0),
+];
+
+const LEARNING_RATE: f64 = 0.02;
+const STEP: f64 = 1e-4;
+
+// how well a set of weights predicts the answers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Fit {
+    // the answers predicted
+    pub reviews: usize,
+    pub log_loss: f64,
+    // the average chance of remembering predicted, and the share actually remembered
+    pub predicted: f64,
+    pub actual: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct Optimized {
+    pub fsrs: Fsrs,
+    // the cards the answers were for
+    pub cards: usize,
+    pub before: Fit,
+    pub after: Fit,
+}
+
+// one card's answers, oldest first: days since the card's previous answer, and the rating
+type Sequence = Vec<(f64, Rating)>;
+
+// fits the weights to the records, starting from start's. the retention is kept. if the fit
+// isn't any better, start's weights are kept
+pub fn optimize(records: &[Record], start: &Fsrs, iterations: usize) -> Result<Optimized, String> {
+    let sequences = sequences(records);
+    let before = evaluate(start, &sequences);
+
+    if before.reviews < MIN_REVIEWS {
+        return Err(format!(
+            "Only {} answers came a day or more after the last one for the same sentence, at least {MIN_REVIEWS} are needed",
+            before.reviews
+        ));
+    }
+
+    let mut fsrs = start.clone();
+    let mut best = (before.log_loss, start.weights);
+    // Adam's moving averages of the gradient and its square
+    let mut mean = [0.0; 17];
+    let mut variance = [0.0; 17];
+
+    for iteration in 1..=iterations {
+        let gradient = gradient(&fsrs, &sequences);
+
+        for i in 0..17 {
+            mean[i] = 0.9 * mean[i] + 0.1 * gradient[i];
+            variance[i] = 0.999 * variance[i] + 0.001 * gradient[i] * gradient[i];
+
+            let mean = mean[i] / (1.0 - 0.9f64.powi(iteration as i32));
+            let variance = variance[i] / (1.0 - 0.999f64.powi(iteration as i32));
+
+            let (low, high) = BOUNDS[i];
+            fsrs.weights[i] = (fsrs.weights[i] - LEARNING_RATE * mean / (variance.sqrt() + 1e-8))
+                .clamp(low, high);
+        }
+
+        let loss = evaluate(&fsrs, &sequences).log_loss;
+        if loss < best.0 {
+            best = (loss, fsrs.weights);
+        }
+    }
+
+    fsrs.weights = best.1;
+
+    Ok(Optimized {
+        after: evaluate(&fsrs, &sequences),
+        cards: sequences.len(),
+        fsrs,
+        before,
+    })
+}
+
+// the answers in the language being learnt, grouped by card
+fn sequences(records: &[Record]) -> Vec<Sequence> {
+    let mut cards: BTreeMap<(i32, &str), Vec<&Record>> = BTreeMap::new();
+
+    // the blank is english in inverse mode, so it isn't a card
+    for record in records.iter().filter(|record| !record.inverse) {
+        cards
+            .entry((record.sentence, record.word.as_str()))
+            .or_default()
+            .push(record);
+    }
+
+    cards
+        .into_values()
+        .map(|mut answers| {
+            answers.sort_by_key(|record| record.timestamp);
+
+            let mut previous = None;
+            answers
+                .into_iter()
+                .map(|record| {
+                    let elapsed = previous.map_or(0.0, |previous| {
+                        record.timestamp.saturating_sub(previous) as f64 / DAY
+                    });
+                    previous = Some(record.timestamp);
+                    (elapsed, Rating::from(record.grade))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn evaluate(fsrs: &Fsrs, sequences: &[Sequence]) -> Fit {
+    let mut reviews = 0;
+    let mut loss = 0.0;
+    let mut predicted = 0.0;
+    let mut remembered = 0;
+
+    for sequence in sequences {
+        let mut card = Card::new(0, "", 0);
+
+        for (index, (elapsed, rating)) in sequence.iter().enumerate() {
+            if index > 0 && *elapsed >= 1.0 {
+                let recall = Fsrs::retrievability(*elapsed, card.stability).clamp(1e-6, 1.0 - 1e-6);
+                let recalled = *rating != Rating::Again;
+
+                loss -= if recalled {
+                    recall.ln()
+                } else {
+                    (1.0 - recall).ln()
+                };
+                predicted += recall;
+                remembered += usize::from(recalled);
+                reviews += 1;
+            }
+
+            card.fsrs(*rating, *elapsed, fsrs);
+        }
+    }
+
+    let count = reviews.max(1) as f64;
+
+    Fit {
+        reviews,
+        log_loss: loss / count,
+        predicted: predicted / count,
+        actual: remembered as f64 / count,
+    }
+}
+
+fn gradient(fsrs: &Fsrs, sequences: &[Sequence]) -> [f64; 17] {
+    let mut gradient = [0.0; 17];
+    let mut nudged = fsrs.clone();
+
+    for (i, slope) in gradient.iter_mut().enumerate() {
+        let weight = fsrs.weights[i];
+
+        nudged.weights[i] = weight + STEP;
+        let up = evaluate(&nudged, sequences).log_loss;
+        nudged.weights[i] = weight - STEP;
+        let down = evaluate(&nudged, sequences).log_loss;
+        nudged.weights[i] = weight;
+
+        *slope = (up - down) / (2.0 * STEP);
+    }
+
+    gradient
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grading::Grade;
+
+    // cards answered on the same days, and forgotten far more often than FSRS's defaults
+    // expect: every other answer after a gap is wrong
+    fn forgetful(cards: i32) -> Vec<Record> {
+        let days = [0, 2, 5, 6, 12, 13, 25];
+
+        (0..cards)
+            .flat_map(|card| {
+                days.iter().enumerate().map(move |(index, day)| Record {
+                    sentence: card,
+                    language: String::from("fra"),
+                    native: String::from("eng"),
+                    inverse: false,
+                    word: String::from("chat"),
+                    guess: String::from("chat"),
+                    grade: if index % 2 == 0 {
+                        Grade::Correct
+                    } else {
+                        Grade::Wrong
+                    },
+                    response_ms: None,
+                    timestamp: 1_700_000_000 + day * DAY as u64,
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fits_weights_better_than_the_defaults() {
+        let records = forgetful(12);
+        let optimized = optimize(&records, &Fsrs::default(), 40).unwrap();
+
+        assert_eq!(optimized.cards, 12);
+        assert_eq!(optimized.before.reviews, 12 * 6);
+        assert!(optimized.after.log_loss < optimized.before.log_loss);
+        // half the answers were remembered, and the fit expects fewer than the defaults did
+        assert_eq!(optimized.after.actual, 0.5);
+        assert!(optimized.after.predicted < optimized.before.predicted);
+        assert_eq!(optimized.fsrs.retention, Fsrs::default().retention);
+    }
+
+    #[test]
+    fn needs_enough_answers() {
+        assert!(optimize(&forgetful(2), &Fsrs::default(), 40).is_err());
+
+        // inverse answers aren't cards
+        let mut records = forgetful(12);
+        for record in &mut records {
+            record.inverse = true;
+        }
+        assert!(optimize(&records, &Fsrs::default(), 40).is_err());
+    }
+}
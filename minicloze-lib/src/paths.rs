@@ -0,0 +1,51 @@
+// where minicloze keeps files between sessions, and its config file
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+static DATA_LOCK: Mutex<()> = Mutex::new(());
+
+// e.g. ~/.local/share/minicloze on linux
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("minicloze"))
+}
+
+// a file inside a subdirectory of the data dir, creating the subdirectory if needed
+pub fn data_file(subdir: &str, name: &str) -> Result<PathBuf, String> {
+    let dir = data_dir()
+        .ok_or("Couldn't find a data directory")?
+        .join(subdir);
+
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Couldn't create {}: {e}", dir.display()))?;
+    Ok(dir.join(name))
+}
+
+// held while a file in the data dir is loaded, changed and saved again, so games played at the
+// same time in one process, e.g. on the web server, don't undo each other's changes
+pub fn lock_data() -> MutexGuard<'static, ()> {
+    // nothing is left half done behind the lock, the files are what's protected
+    DATA_LOCK.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// e.g. ~/.config/minicloze/config.toml on linux, creating the directory if needed
+pub fn config_file() -> Result<PathBuf, String> {
+    let dir = dirs::config_dir()
+        .ok_or("Couldn't find a config directory")?
+        .join("minicloze");
+
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Couldn't create {}: {e}", dir.display()))?;
+    Ok(dir.join("config.toml"))
+}
+
+// points the data dir at a fresh temporary one for the tests. every test shares it, so each
+// uses its own language to keep out of the others' way
+#[cfg(test)]
+pub fn isolate() {
+    static DATA_DIR: std::sync::Once = std::sync::Once::new();
+
+    DATA_DIR.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("minicloze-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::env::set_var("XDG_DATA_HOME", dir);
+    });
+}
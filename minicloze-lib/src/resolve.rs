@@ -0,0 +1,213 @@
+// turns whatever the user typed into a tatoeba language. accepts tatoeba's own codes ("fra"),
+// ISO 639-1 codes ("fr"), BCP-47 tags ("pt-BR", "zh-yue"), names in any case and with or
+// without accents ("norwegian bokmal") and a few common aliases ("farsi")
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::grading::{distance, strip_accents};
+use crate::langs::Language;
+
+// how many languages a misspelling suggests at most
+const MAX_SUGGESTIONS: usize = 3;
+
+// other names people use for languages, already lowercased and without accents
+const ALIASES: [(&str, &str); 37] = [
+    // ISO 639-1 has both "nb" for bokmål and "no" for norwegian in general
+    ("no", "nob"),
+    ("chinese", "cmn"),
+    ("mandarin", "cmn"),
+    ("putonghua", "cmn"),
+    ("hokkien", "nan"),
+    ("taiwanese", "nan"),
+    ("wu", "wuu"),
+    ("farsi", "pes"),
+    ("filipino", "tgl"),
+    ("flemish", "nld"),
+    ("castilian", "spa"),
+    ("valencian", "cat"),
+    ("moldovan", "ron"),
+    ("slovene", "slv"),
+    ("norwegian", "nob"),
+    ("bokmal", "nob"),
+    ("nynorsk", "nno"),
+    ("kurdish", "kmr"),
+    ("kurmanji", "kmr"),
+    ("sorani", "ckb"),
+    ("gaelic", "gla"),
+    ("scots gaelic", "gla"),
+    ("punjabi", "pan"),
+    ("panjabi", "pan"),
+    ("odia", "ori"),
+    ("oriya", "ori"),
+    ("haitian", "hat"),
+    ("tamazight", "zgh"),
+    ("sesotho", "sot"),
+    ("tswana", "tsn"),
+    ("chichewa", "nya"),
+    ("nyanja", "nya"),
+    ("kalaallisut", "kal"),
+    ("kirghiz", "kir"),
+    ("myanmar", "mya"),
+    ("sinhalese", "sin"),
+    ("uighur", "uig"),
+];
+
+// normalised names, also without a parenthesised part, e.g. "punjabi" for "Punjabi (Eastern)".
+// full names take precedence over shortened ones, then languages with more sentences
+static NAMES: LazyLock<HashMap<String, &'static Language>> = LazyLock::new(|| {
+    let mut names = HashMap::new();
+
+    for language in Language::all() {
+        let short = language.name.split(" (").next().unwrap_or(language.name);
+        names.entry(normalise(short)).or_insert(language);
+    }
+    for language in Language::all() {
+        names.insert(normalise(language.name), language);
+    }
+    // english names win where an endonym is also another language's name
+    for language in Language::all() {
+        if let Some(endonym) = language.endonym {
+            names.entry(normalise(endonym)).or_insert(language);
+        }
+    }
+
+    names
+});
+
+// the language the input means, or an error suggesting languages it might be a misspelling of
+pub fn resolve_language(input: &str) -> Result<&'static Language, String> {
+    let key = normalise(input);
+
+    let found = Language::from_code(&key)
+        .or_else(|| Language::from_iso1(&key))
+        .or_else(|| alias(&key))
+        .or_else(|| NAMES.get(key.as_str()).copied())
+        .or_else(|| bcp47(&key));
+
+    if let Some(language) = found {
+        return Ok(language);
+    }
+
+    let suggestions = suggest(input);
+    let mut message = format!("\"{}\" isn't a language on Tatoeba", input.trim());
+
+    if let Some((last, rest)) = suggestions.split_last() {
+        message.push_str(", did you mean ");
+        if !rest.is_empty() {
+            message.push_str(&rest.join(", "));
+            message.push_str(" or ");
+        }
+        message.push_str(&format!("{last}?"));
+    }
+
+    Err(message)
+}
+
+// the names closest to a misspelt language, best first
+pub fn suggest(input: &str) -> Vec<&'static str> {
+    let key = normalise(input);
+
+    if key.is_empty() {
+        return Vec::new();
+    }
+
+    // a third of the input may be wrong, so short inputs don't match everything
+    let allowed = (key.chars().count() / 3).max(1);
+
+    let mut candidates: Vec<(usize, usize, &'static str)> = Language::all()
+        .iter()
+        .filter_map(|language| {
+            let name = language.name;
+            let normalised = normalise(name);
+            let short = normalise(name.split(" (").next().unwrap_or(name));
+
+            // "sami" -> "Northern Sami" and "Southern Sami"
+            if normalised.split_whitespace().any(|word| word == key) {
+                return Some((0, language.rank, name));
+            }
+
+            let distance = distance(&key, &normalised).min(distance(&key, &short));
+            (distance <= allowed).then_some((distance, language.rank, name))
+        })
+        .collect();
+
+    candidates.sort();
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, _, name)| name)
+        .collect()
+}
+
+// lowercase, without accents, and with runs of whitespace as one space
+fn normalise(input: &str) -> String {
+    strip_accents(&input.to_lowercase())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn alias(key: &str) -> Option<&'static Language> {
+    ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .and_then(|(_, code)| Language::from_code(code))
+}
+
+// "pt-br" or "pt_br" -> "por", "zh-hant-tw" -> "cmn". an extended language subtag naming a
+// tatoeba language wins over the primary one, so "zh-yue" is cantonese
+fn bcp47(key: &str) -> Option<&'static Language> {
+    let mut subtags = key.split(['-', '_']);
+    let primary = subtags.next()?;
+
+    if !(2..=3).contains(&primary.len()) || !primary.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let code = |subtag: &str| Language::from_code(subtag).or_else(|| Language::from_iso1(subtag));
+
+    match subtags.next() {
+        Some(extlang) if extlang.len() == 3 && code(extlang).is_some() => code(extlang),
+        Some(_) => code(primary),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_codes_names_and_tags() {
+        let table = [
+            ("fra", "fra"),
+            ("FRENCH", "fra"),
+            ("fr", "fra"),
+            (" Mandarin   chinese ", "cmn"),
+            ("mandarin", "cmn"),
+            ("pt-BR", "por"),
+            ("zh-yue", "yue"),
+            ("zh_Hant_TW", "cmn"),
+            ("Français", "fra"),
+            ("francais", "fra"),
+            ("日本語", "jpn"),
+            ("farsi", "pes"),
+        ];
+
+        for (input, code) in table {
+            assert_eq!(resolve_language(input).map(|l| l.code), Ok(code), "{input}");
+        }
+    }
+
+    #[test]
+    fn suggests_languages_for_misspellings() {
+        assert_eq!(suggest("frnech").first(), Some(&"French"));
+        assert_eq!(
+            resolve_language("Germna").unwrap_err(),
+            "\"Germna\" isn't a language on Tatoeba, did you mean German?"
+        );
+        assert!(resolve_language("xx").is_err());
+        assert!(suggest("").is_empty());
+    }
+}
@@ -1,12 +1,17 @@
 // logic which handles parsing a raw JSON from tatoeba into sentences
 
-use rand::{thread_rng, Rng};
-use reqwest::Error;
+use rand::{seq::SliceRandom, thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 
-const NON_SPACED: [&str; 12] = [
-    "cmn", "lzh", "hak", "cjy", "nan", "hsn", "gan", "jpn", "tha", "khm", "lao", "mya",
-];
+use crate::langs::is_spaced;
+
+// how many sentences a round has unless asked otherwise
+pub const ROUND_SIZE: usize = 10;
+
+// requests made for one round at most, some languages don't have enough sentences to fill it
+const MAX_REQUESTS: usize = 5;
+// sentences tatoeba returns for a request at most
+const MAX_LIMIT: usize = 100;
 
 // represents the entire JSON response from Tatoeba. results is the sentences found.
 #[derive(Deserialize, Serialize)]
@@ -14,10 +19,10 @@ pub struct Json {
     pub data: Vec<Sentence>,
 }
 
-// represents a sentence. id is the tatoeba id of the sentence, not used anywhere currently
+// represents a sentence. id is the tatoeba id of the sentence
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Sentence {
-    id: i32,
+    pub id: i32,
     pub text: String,
     pub translations: Vec<Translation>,
 }
@@ -25,17 +30,73 @@ pub struct Sentence {
 // represents a translation. id is the tatoeba id of the translation
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct Translation {
-    id: i32,
+    pub id: i32,
     pub text: String,
+    // older cached sentences don't have these
+    #[serde(default)]
+    pub transcriptions: Vec<Transcription>,
+}
+
+// the sentence in another script, e.g. "Hrkt" for japanese with furigana, written like
+// "[猫|ねこ]が[好|す]き". a base spanning several characters has one reading per character
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct Transcription {
+    pub script: String,
+    pub text: String,
+}
+
+// how the word to blank is picked
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    #[default]
+    Random,
+    // the longest word, which is less likely to be a particle or article
+    Longest,
 }
 
-#[derive(Clone)]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 pub struct Prompt {
     pub first_half: String,
     pub word: String,
     pub second_half: String,
 }
 
+impl Prompt {
+    // what goes between the blank and the rest of the sentence: a space, as the words were split
+    // on, unless the language is written without them or the blank ends the sentence
+    pub fn separator(&self, language: &str) -> &'static str {
+        if is_spaced(language) && !self.second_half.is_empty() {
+            " "
+        } else {
+            ""
+        }
+    }
+}
+
+impl Translation {
+    // the japanese reading with the furigana in brackets, e.g. "猫(ねこ)が好(す)き"
+    pub fn furigana(&self) -> Option<String> {
+        let transcription = self.transcriptions.iter().find(|t| t.script == "Hrkt")?;
+        let mut text = String::new();
+
+        for part in transcription.text.split_inclusive(']') {
+            match part.split_once('[') {
+                Some((before, ruby)) => {
+                    let ruby = ruby.trim_end_matches(']');
+                    let (base, readings) = ruby.split_once('|').unwrap_or((ruby, ""));
+
+                    text.push_str(before);
+                    text.push_str(&format!("{base}({})", readings.replace('|', "")));
+                }
+                None => text.push_str(part),
+            }
+        }
+
+        Some(text)
+    }
+}
+
 impl Sentence {
     // get the sentence's translation
     // sometimes translations.0 will be blank
@@ -44,15 +105,16 @@ impl Sentence {
     }
 
     // split string into vec of words, depends on whether the language uses spaces or not (e.g.
-    // japanese is not spaced)
+    // japanese is not spaced). a sentence without a translation has no words to blank outside
+    // inverse mode
     pub fn as_words(&self, language: &str, inverse: bool) -> Vec<String> {
         let translation = if inverse {
             &self.text
         } else {
-            &self.get_translation().unwrap().text
+            self.get_translation().map_or("", |t| t.text.as_str())
         };
 
-        let words: Vec<String> = if NON_SPACED.contains(&language) {
+        let words: Vec<String> = if !is_spaced(language) {
             let char_strings = translation.trim().chars().map(|x| x.to_string());
             char_strings.collect::<Vec<String>>()
         } else {
@@ -68,52 +130,124 @@ impl Sentence {
 
     // splits a sentence into a prompt consisting of three parts
     pub fn generate_prompt(&self, language: &str, inverse: bool) -> Prompt {
+        self.generate_prompt_with(language, inverse, Strategy::Random, &mut thread_rng())
+    }
+
+    // like generate_prompt, with a choice of strategy and a random number generator, which can
+    // be seeded for reproducible rounds
+    pub fn generate_prompt_with(
+        &self,
+        language: &str,
+        inverse: bool,
+        strategy: Strategy,
+        rng: &mut impl Rng,
+    ) -> Prompt {
+        let words: Vec<String> = self.as_words(language, inverse);
+
+        let index = match strategy {
+            Strategy::Random => rng.gen_range(0..words.len().max(1)),
+            Strategy::Longest => {
+                let length = |word: &String| remove_punctuation(word).chars().count();
+                let longest = words.iter().map(length).max().unwrap_or(0);
+
+                // ties are broken randomly so the same sentence doesn't always blank the same word
+                let candidates: Vec<usize> = (0..words.len())
+                    .filter(|i| length(&words[*i]) == longest)
+                    .collect();
+                *candidates.choose(rng).unwrap_or(&0)
+            }
+        };
+
+        self.prompt_at(language, inverse, index)
+    }
+
+    // the prompt blanking the word at index, as counted by as_words
+    pub fn prompt_at(&self, language: &str, inverse: bool, index: usize) -> Prompt {
         let words: Vec<String> = self.as_words(language, inverse);
-        let halved = words.split_at(thread_rng().gen_range(0..words.len()));
+        let (first_half, rest) = words.split_at(index.min(words.len().saturating_sub(1)));
 
-        let word = remove_punctuation(&halved.1[0]);
+        let Some((word, second_half)) = rest.split_first() else {
+            return Prompt::default();
+        };
 
         Prompt {
-            first_half: halved.0.join(""),
-            word,
-            second_half: halved.1[1..].join(""),
+            first_half: first_half.join(""),
+            word: remove_punctuation(word),
+            second_half: second_half.join(""),
         }
     }
+
+    // where a word appears in the sentence, ignoring case and punctuation
+    pub fn find_word(&self, language: &str, inverse: bool, word: &str) -> Option<usize> {
+        let word = word.trim().to_lowercase();
+
+        self.as_words(language, inverse)
+            .iter()
+            .position(|w| remove_punctuation(w).to_lowercase() == word)
+    }
 }
 
 // language: the language to request from tatoeba
-pub async fn generate_sentences(language: &str) -> std::result::Result<Vec<Sentence>, Error> {
-    // where the initial request happens
-    let mut sentences = sentences_http_request(language).await?;
-
-    let len = sentences.len();
-
-    // makes sure we always get 10 sentences
-    if len != 10 {
-        let difference = 10 - len;
-        // makes more requests if required
-        let mut sentences_difference = sentences_http_request(language)
-            .await?
-            .into_iter()
-            .take(difference)
-            .collect::<Vec<_>>();
-
-        sentences.append(&mut sentences_difference);
-    }
-    Ok(sentences)
+pub async fn generate_sentences(language: &str) -> Result<Vec<Sentence>, String> {
+    fetch_sentences(language, "eng", ROUND_SIZE).await
 }
 
 // language: the language to request from tatoeba
-pub async fn sentences_http_request(language: &str) -> Result<Vec<Sentence>, Error> {
-    let request = format!("https://api.tatoeba.org/v1/sentences?lang=eng&is_orphan=no&sort=random&trans:lang={language}&showtrans:lang={language}");
-    let response = reqwest::get(request).await?.text().await?;
+// native: the language the sentences are translated from, english by default
+// count: how many sentences to get, fewer are returned if tatoeba runs out
+pub async fn fetch_sentences(
+    language: &str,
+    native: &str,
+    count: usize,
+) -> Result<Vec<Sentence>, String> {
+    let mut sentences: Vec<Sentence> = Vec::new();
 
-    let resp_str = response.as_str();
+    // makes more requests if required
+    for _ in 0..MAX_REQUESTS {
+        if sentences.len() >= count {
+            break;
+        }
+
+        let limit = (count - sentences.len()).min(MAX_LIMIT);
+        let batch = sentences_http_request(language, native, limit).await?;
+        if batch.is_empty() {
+            break;
+        }
 
-    let sentences = parse(resp_str).unwrap();
+        for sentence in batch {
+            // a sentence without a translation can't be played
+            if sentence.get_translation().is_some()
+                && !sentences.iter().any(|s| s.id == sentence.id)
+            {
+                sentences.push(sentence);
+            }
+        }
+    }
+
+    sentences.truncate(count);
     Ok(sentences)
 }
 
+// language: the language to request from tatoeba
+// native: the language of the sentences the translations belong to
+// limit: how many sentences to ask for, up to MAX_LIMIT
+pub async fn sentences_http_request(
+    language: &str,
+    native: &str,
+    limit: usize,
+) -> Result<Vec<Sentence>, String> {
+    let request = format!("https://api.tatoeba.org/v1/sentences?lang={native}&is_orphan=no&sort=random&trans:lang={language}&showtrans:lang={language}&limit={limit}");
+    let response = reqwest::get(request)
+        .await
+        .map_err(|e| e.to_string())?
+        .text()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    // tatoeba sends an html error page when it's down
+    parse(&response).map_err(|e| format!("Tatoeba sent something other than sentences: {e}"))
+}
+
 // converts a serde error into a string
 pub fn convert_error(err: serde_json::Error) -> String {
     format!(
@@ -138,3 +272,49 @@ pub fn remove_punctuation(word: &str) -> String {
         "",
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentence(translation: &str) -> Sentence {
+        Sentence {
+            id: 1,
+            text: String::from("The cat is sleeping."),
+            translations: vec![Translation {
+                id: 2,
+                text: translation.to_string(),
+                transcriptions: Vec::new(),
+            }],
+        }
+    }
+
+    fn joined(prompt: &Prompt, language: &str) -> String {
+        format!(
+            "{}{}{}{}",
+            prompt.first_half,
+            prompt.word,
+            prompt.separator(language),
+            prompt.second_half
+        )
+    }
+
+    #[test]
+    fn joins_the_blank_back_into_the_sentence() {
+        let french = sentence("Le chat dort .");
+        let prompt = french.prompt_at("fra", false, 1);
+        assert_eq!(prompt.word, "chat");
+        assert_eq!(joined(&prompt, "fra"), "Le chat dort .");
+
+        // nothing is left after the last word
+        let prompt = sentence("Le chat dort.").prompt_at("fra", false, 2);
+        assert_eq!(prompt.word, "dort");
+        assert_eq!(prompt.second_half, "");
+        assert_eq!(prompt.separator("fra"), "");
+
+        let japanese = sentence("猫が寝ている。");
+        let prompt = japanese.prompt_at("jpn", false, 0);
+        assert_eq!(prompt.word, "猫");
+        assert_eq!(joined(&prompt, "jpn"), "猫が寝ている。");
+    }
+}
@@ -0,0 +1,751 @@
+// a game of minicloze, without any of how it's shown. a session plays rounds of sentences:
+//
+//     Loading --load_round--> Prompting --submit--> Answered --advance--> Prompting ...
+//                                                            \--advance--> Finished
+//     Finished --replay--> Loading
+//
+// frontends show the current prompt, pass guesses on and show the feedback they get back.
+// everything that happens is also kept as events, for frontends which would rather react to
+// those. every answer is recorded in the answer history, see history.rs, and scheduled for
+// review, see srs.rs. misses are also kept for review rounds, see missed.rs, and words
+// answered right are known, see known.rs, so new sentences are picked around them. a session
+// serializes to JSON, so a game can be put down and picked up again
+
+use std::time::Instant;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::cache;
+use crate::grading::{grade, grade_with_lexicon, Feedback, Grade, GradingPolicy};
+use crate::history::{self, Record};
+use crate::known::KnownWords;
+use crate::missed::{self, Miss, MissFilter};
+use crate::morph::{Lexicon, WordProgress};
+use crate::paths::lock_data;
+use crate::sentence::{fetch_sentences, Prompt, Sentence, Strategy};
+use crate::srs::{Deck, Fsrs, Rating, Scheduler};
+
+// how many times as many new sentences as a round needs are picked from, once some words are
+// known
+const OVERSAMPLE: usize = 4;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    // fresh sentences from tatoeba.org, which are also cached
+    Tatoeba,
+    // sentences cached by earlier games or `minicloze cache fetch`
+    Cache,
+}
+
+// everything about a game that stays the same between rounds
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SessionOptions {
+    // tatoeba codes
+    pub language: String,
+    pub native: String,
+    pub inverse: bool,
+    pub round_size: usize,
+    pub strategy: Strategy,
+    pub source: Source,
+    // play prompts answered wrongly or nearly before again, instead of new sentences
+    pub review: bool,
+    // which of those prompts review rounds pick from
+    #[serde(default)]
+    pub filter: MissFilter,
+    pub grading: GradingPolicy,
+    // how cards are scheduled, and whether rounds start with the ones due
+    #[serde(default)]
+    pub scheduler: Scheduler,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Phase {
+    Loading,
+    Prompting,
+    Answered,
+    Finished,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Summary {
+    // this round
+    pub correct: usize,
+    pub total: usize,
+    // every round of the session, this one included
+    pub overall_correct: usize,
+    pub overall_total: usize,
+    pub rounds: usize,
+}
+
+// the current prompt as the learner sees it, for frontends which aren't given the word to
+// guess before it's answered, e.g. over a network
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct PromptView {
+    // of the round's sentences
+    pub index: usize,
+    pub total: usize,
+    pub first_half: String,
+    pub second_half: String,
+    // left out in inverse mode, where it would give the english word away
+    pub letters: Option<usize>,
+    // the other sentence of the pair: the translation, or the sentence in the language being
+    // learnt in inverse mode
+    pub other: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    RoundLoaded {
+        sentences: usize,
+    },
+    Prompted {
+        index: usize,
+        prompt: Prompt,
+    },
+    Answered {
+        index: usize,
+        guess: String,
+        feedback: Feedback,
+    },
+    RoundFinished {
+        summary: Summary,
+    },
+}
+
+// an answer in the language being learnt. it's applied to the files as they are when it's
+// given, which other games may have saved to since this one loaded them
+struct Answer {
+    sentence: i32,
+    word: String,
+    lemma: Option<String>,
+    grade: Grade,
+    timestamp: u64,
+}
+
+impl Answer {
+    fn record(&self, progress: &mut WordProgress, known: &mut KnownWords) {
+        let correct = self.grade == Grade::Correct;
+        progress.record(&self.word, self.lemma.as_deref(), correct);
+
+        if correct {
+            known.add(&self.word);
+        }
+    }
+
+    fn review(&self, deck: &mut Deck, scheduler: Scheduler, fsrs: &Fsrs) {
+        deck.review(
+            self.sentence,
+            self.word.trim(),
+            Rating::from(self.grade),
+            scheduler,
+            fsrs,
+            self.timestamp,
+        );
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct GameSession {
+    options: SessionOptions,
+    phase: Phase,
+    // each round is picked with the seed plus the round's number, so a seeded game replays
+    // exactly, and so does a restored one
+    seed: u64,
+    rounds: usize,
+    round: Vec<(Sentence, Prompt)>,
+    // the prompt being answered
+    position: usize,
+    correct: usize,
+    // the rounds before this one
+    previous_correct: usize,
+    previous_total: usize,
+    progress: WordProgress,
+    deck: Deck,
+    fsrs: Fsrs,
+    #[serde(default)]
+    known: KnownWords,
+    #[serde(skip)]
+    events: Vec<Event>,
+    // when the current prompt was shown, for the response time
+    #[serde(skip)]
+    prompted_at: Option<Instant>,
+}
+
+impl GameSession {
+    pub fn new(options: SessionOptions, seed: Option<u64>) -> GameSession {
+        GameSession {
+            // a fresh tally is better than no game
+            progress: WordProgress::load(&options.language).unwrap_or_default(),
+            deck: Deck::load(&options.language, &options.native).unwrap_or_default(),
+            fsrs: Fsrs::load(&options.language).unwrap_or_default(),
+            known: KnownWords::load(&options.language).unwrap_or_default(),
+            options,
+            phase: Phase::Loading,
+            seed: seed.unwrap_or_else(rand::random),
+            rounds: 0,
+            round: Vec::new(),
+            position: 0,
+            correct: 0,
+            previous_correct: 0,
+            previous_total: 0,
+            events: Vec::new(),
+            prompted_at: None,
+        }
+    }
+
+    pub fn options(&self) -> &SessionOptions {
+        &self.options
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    // the round's sentences, each with the word to blank
+    pub fn round(&self) -> &[(Sentence, Prompt)] {
+        &self.round
+    }
+
+    // the index in the round of the prompt being answered
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    // the prompt being answered or just answered, None between rounds
+    pub fn current(&self) -> Option<&(Sentence, Prompt)> {
+        match self.phase {
+            Phase::Prompting | Phase::Answered => self.round.get(self.position),
+            Phase::Loading | Phase::Finished => None,
+        }
+    }
+
+    // the current prompt without its word, None between rounds
+    pub fn view(&self) -> Option<PromptView> {
+        let (sentence, prompt) = self.current()?;
+        let inverse = self.options.inverse;
+
+        // tatoeba's sentence is the native one, its translation is in the language being learnt
+        let other = if inverse {
+            sentence.get_translation().map(|t| t.text.clone())
+        } else {
+            Some(sentence.text.clone())
+        };
+
+        Some(PromptView {
+            index: self.position,
+            total: self.round.len(),
+            first_half: prompt.first_half.clone(),
+            second_half: prompt.second_half.clone(),
+            letters: (!inverse).then(|| prompt.word.chars().count()),
+            other,
+        })
+    }
+
+    // the number of sentences in the new round. it's 0 if there weren't any, and the session
+    // stays loading
+    pub async fn load_round(&mut self) -> Result<usize, String> {
+        self.expect(Phase::Loading, "load a round")?;
+
+        let mut rng = StdRng::seed_from_u64(self.seed.wrapping_add(self.rounds as u64));
+
+        let round = if self.options.review {
+            self.review_round(&mut rng)?
+        } else {
+            self.new_round(&mut rng).await?
+        };
+
+        if round.is_empty() {
+            return Ok(0);
+        }
+
+        self.round = round;
+        self.rounds += 1;
+        self.position = 0;
+        self.correct = 0;
+        self.phase = Phase::Prompting;
+
+        self.events.push(Event::RoundLoaded {
+            sentences: self.round.len(),
+        });
+        self.prompted();
+
+        Ok(self.round.len())
+    }
+
+    // grades the guess at the current prompt. the lexicon, if there is one for the language,
+    // recognises wrong inflections
+    pub fn submit(&mut self, guess: &str, lexicon: Option<&Lexicon>) -> Result<Feedback, String> {
+        self.expect(Phase::Prompting, "answer")?;
+
+        let (sentence, prompt) = self.round[self.position].clone();
+        let inverse = self.options.inverse;
+        // in inverse mode the blank is in the native language, so it isn't what's being learnt
+        let learnt = !inverse;
+
+        let feedback = match lexicon {
+            Some(lexicon) if learnt => {
+                grade_with_lexicon(guess, &prompt.word, &self.options.grading, lexicon)
+            }
+            _ => grade(guess, &prompt.word, &self.options.grading),
+        };
+
+        if learnt {
+            let answer = Answer {
+                sentence: sentence.id,
+                word: prompt.word.clone(),
+                lemma: lexicon
+                    .and_then(|l| l.lemma_of(&prompt.word))
+                    .map(str::to_string),
+                grade: feedback.grade,
+                timestamp: Record::now(),
+            };
+
+            self.save(&answer);
+        }
+
+        if learnt && feedback.grade != Grade::Correct {
+            // the game goes on without it
+            let _ = missed::record(
+                &self.options.language,
+                &self.options.native,
+                Miss {
+                    sentence: sentence.clone(),
+                    prompt: prompt.clone(),
+                    guess: guess.trim().to_string(),
+                    grade: feedback.grade,
+                    mistake: feedback.mistake,
+                    timestamp: Record::now(),
+                },
+            );
+        }
+
+        // a miss put right in a review round doesn't need reviewing again
+        if learnt && self.options.review && feedback.grade == Grade::Correct {
+            let _ = missed::resolve(
+                &self.options.language,
+                &self.options.native,
+                sentence.id,
+                &prompt.word,
+            );
+        }
+
+        // the game goes on without a history
+        let _ = history::append(&Record {
+            sentence: sentence.id,
+            language: self.options.language.clone(),
+            native: self.options.native.clone(),
+            inverse,
+            word: prompt.word.trim().to_string(),
+            guess: guess.trim().to_string(),
+            grade: feedback.grade,
+            response_ms: self
+                .prompted_at
+                .map(|shown| shown.elapsed().as_millis() as u64),
+            timestamp: Record::now(),
+        });
+
+        if feedback.grade == Grade::Correct {
+            self.correct += 1;
+        }
+        self.phase = Phase::Answered;
+
+        self.events.push(Event::Answered {
+            index: self.position,
+            guess: guess.to_string(),
+            feedback: feedback.clone(),
+        });
+
+        Ok(feedback)
+    }
+
+    // moves on to the next prompt, or finishes the round with its summary
+    pub fn advance(&mut self) -> Result<Option<Summary>, String> {
+        self.expect(Phase::Answered, "move on")?;
+
+        self.position += 1;
+
+        if self.position < self.round.len() {
+            self.phase = Phase::Prompting;
+            self.prompted();
+            return Ok(None);
+        }
+
+        self.phase = Phase::Finished;
+        let summary = self.summary();
+        self.events.push(Event::RoundFinished { summary });
+
+        Ok(Some(summary))
+    }
+
+    // whether the current prompt's card has been forgotten so often it's a leech
+    pub fn leech(&self) -> bool {
+        self.current().is_some_and(|(sentence, prompt)| {
+            self.deck
+                .card(sentence.id, prompt.word.trim())
+                .is_some_and(|card| card.leech)
+        })
+    }
+
+    pub fn summary(&self) -> Summary {
+        let answered = match self.phase {
+            Phase::Finished => self.round.len(),
+            Phase::Answered => self.position + 1,
+            Phase::Loading | Phase::Prompting => self.position,
+        };
+
+        Summary {
+            correct: self.correct,
+            total: self.round.len(),
+            overall_correct: self.previous_correct + self.correct,
+            overall_total: self.previous_total + answered,
+            rounds: self.rounds,
+        }
+    }
+
+    // gets ready for another round, after load_round
+    pub fn replay(&mut self) -> Result<(), String> {
+        self.expect(Phase::Finished, "play again")?;
+
+        self.previous_correct += self.correct;
+        self.previous_total += self.round.len();
+        self.correct = 0;
+        self.round.clear();
+        self.position = 0;
+        self.phase = Phase::Loading;
+
+        Ok(())
+    }
+
+    // what's happened since the last call
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn expect(&self, phase: Phase, action: &str) -> Result<(), String> {
+        if self.phase == phase {
+            Ok(())
+        } else {
+            Err(format!(
+                "Can't {action} while the session is {:?}",
+                self.phase
+            ))
+        }
+    }
+
+    // applies an answer to the progress, known words and deck as they've been saved since, and
+    // saves them again, so a game left mid-round keeps what was answered. losing the tally isn't
+    // worth interrupting the game over, so if a file can't be read the answer only counts here
+    fn save(&mut self, answer: &Answer) {
+        let options = &self.options;
+        let _lock = lock_data();
+
+        match (
+            WordProgress::load(&options.language),
+            KnownWords::load(&options.language),
+        ) {
+            (Ok(mut progress), Ok(mut known)) => {
+                answer.record(&mut progress, &mut known);
+                let _ = progress.save(&options.language);
+                let _ = known.save(&options.language);
+                self.progress = progress;
+                self.known = known;
+            }
+            _ => answer.record(&mut self.progress, &mut self.known),
+        }
+
+        if options.scheduler == Scheduler::Off {
+            return;
+        }
+
+        match Deck::load(&options.language, &options.native) {
+            Ok(mut deck) => {
+                answer.review(&mut deck, options.scheduler, &self.fsrs);
+                let _ = deck.save(&options.language, &options.native);
+                self.deck = deck;
+            }
+            Err(_) => answer.review(&mut self.deck, options.scheduler, &self.fsrs),
+        }
+    }
+
+    fn prompted(&mut self) {
+        self.prompted_at = Some(Instant::now());
+        self.events.push(Event::Prompted {
+            index: self.position,
+            prompt: self.round[self.position].1.clone(),
+        });
+    }
+
+    // the cards due for review, topped up with new sentences. once some words are known, more
+    // sentences are fetched than needed, and the ones with the fewest other words unknown kept
+    async fn new_round(&self, rng: &mut StdRng) -> Result<Vec<(Sentence, Prompt)>, String> {
+        let options = &self.options;
+        let mut round = self.due_round()?;
+        let wanted = options.round_size.saturating_sub(round.len());
+        let sampled = if self.known.words.is_empty() {
+            wanted
+        } else {
+            wanted * OVERSAMPLE
+        };
+
+        let sentences = match options.source {
+            _ if wanted == 0 => Vec::new(),
+            Source::Tatoeba => {
+                let sentences =
+                    fetch_sentences(&options.language, &options.native, sampled).await?;
+
+                // kept so the language can be played offline later
+                let _ = cache::store(&options.language, &options.native, &sentences);
+                sentences
+            }
+            Source::Cache => cache::sample(&options.language, &options.native, sampled, rng)?,
+        };
+
+        let reviews = round.len();
+        let mut new = Vec::new();
+
+        for sentence in sentences {
+            if round.iter().any(|(s, _)| s.id == sentence.id) {
+                continue;
+            }
+
+            let prompt = sentence.generate_prompt_with(
+                &options.language,
+                options.inverse,
+                options.strategy,
+                rng,
+            );
+            new.push((sentence, prompt));
+        }
+
+        // stable, so sentences as comprehensible as each other stay in random order
+        new.sort_by_cached_key(|(sentence, prompt)| {
+            self.known
+                .unknown_around(sentence, prompt, &options.language, options.inverse)
+        });
+        round.extend(new.into_iter().take(wanted));
+
+        // so the reviews aren't all at the start
+        if reviews > 0 {
+            round.shuffle(rng);
+        }
+
+        Ok(round)
+    }
+
+    // the cards due, blanking the same words in the same cached sentences. there are none in
+    // inverse mode, where the blank is english
+    fn due_round(&self) -> Result<Vec<(Sentence, Prompt)>, String> {
+        let options = &self.options;
+
+        if options.inverse || options.scheduler == Scheduler::Off {
+            return Ok(Vec::new());
+        }
+
+        let due = self.deck.due(Record::now());
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cached = cache::load(&options.language, &options.native)?;
+
+        Ok(due
+            .into_iter()
+            .filter_map(|card| {
+                let sentence = cached.iter().find(|s| s.id == card.sentence)?;
+                let index = sentence.find_word(&options.language, false, &card.word)?;
+                let prompt = sentence.prompt_at(&options.language, false, index);
+                Some((sentence.clone(), prompt))
+            })
+            .take(options.round_size)
+            .collect())
+    }
+
+    // prompts answered wrongly or nearly before, blanking exactly the same words
+    fn review_round(&self, rng: &mut StdRng) -> Result<Vec<(Sentence, Prompt)>, String> {
+        let options = &self.options;
+
+        let mut misses: Vec<Miss> = missed::load(&options.language, &options.native)?
+            .into_iter()
+            .filter(|miss| options.filter.matches(miss))
+            .collect();
+        misses.shuffle(rng);
+
+        Ok(misses
+            .into_iter()
+            .take(options.round_size)
+            .map(|miss| (miss.sentence, miss.prompt))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+    use crate::paths::isolate;
+    use crate::sentence::Translation;
+
+    fn sentence(id: i32, text: &str, translation: &str) -> Sentence {
+        Sentence {
+            id,
+            text: text.to_string(),
+            translations: vec![Translation {
+                id: id + 1000,
+                text: translation.to_string(),
+                transcriptions: Vec::new(),
+            }],
+        }
+    }
+
+    // a session over cached sentences of one word each, so the blank is known
+    fn session(language: &str, review: bool) -> GameSession {
+        scheduled(language, review, Scheduler::Off)
+    }
+
+    fn scheduled(language: &str, review: bool, scheduler: Scheduler) -> GameSession {
+        isolate();
+
+        let sentences = [sentence(1, "cat", "chat"), sentence(2, "dog", "chien")];
+        cache::store(language, "eng", &sentences).unwrap();
+
+        let options = SessionOptions {
+            language: language.to_string(),
+            native: String::from("eng"),
+            inverse: false,
+            round_size: 2,
+            strategy: Strategy::Random,
+            source: Source::Cache,
+            review,
+            filter: MissFilter::default(),
+            grading: GradingPolicy::default(),
+            scheduler,
+        };
+
+        GameSession::new(options, Some(1))
+    }
+
+    fn answer(session: &GameSession) -> String {
+        session.current().unwrap().1.word.clone()
+    }
+
+    #[test]
+    fn plays_rounds() {
+        let mut session = session("fra", false);
+
+        assert_eq!(block_on(session.load_round()), Ok(2));
+        assert_eq!(session.phase(), Phase::Prompting);
+
+        let feedback = session.submit(&answer(&session), None).unwrap();
+        assert_eq!(feedback.grade, Grade::Correct);
+        assert_eq!(session.phase(), Phase::Answered);
+        assert_eq!(session.advance(), Ok(None));
+
+        let feedback = session.submit("oiseau", None).unwrap();
+        assert_eq!(feedback.grade, Grade::Wrong);
+
+        let summary = session.advance().unwrap().unwrap();
+        assert_eq!(session.phase(), Phase::Finished);
+        assert_eq!(
+            summary,
+            Summary {
+                correct: 1,
+                total: 2,
+                overall_correct: 1,
+                overall_total: 2,
+                rounds: 1,
+            }
+        );
+        assert_eq!(session.summary(), summary);
+
+        session.replay().unwrap();
+        assert_eq!(session.phase(), Phase::Loading);
+        assert_eq!(block_on(session.load_round()), Ok(2));
+
+        let summary = session.summary();
+        assert_eq!((summary.correct, summary.total), (0, 2));
+        assert_eq!((summary.overall_correct, summary.overall_total), (1, 2));
+        assert_eq!(summary.rounds, 2);
+    }
+
+    #[test]
+    fn actions_need_the_right_phase() {
+        let mut session = session("deu", false);
+
+        assert!(session.submit("Katze", None).is_err());
+        assert!(session.advance().is_err());
+        assert!(session.replay().is_err());
+
+        block_on(session.load_round()).unwrap();
+        assert!(block_on(session.load_round()).is_err());
+        assert!(session.advance().is_err());
+
+        session.submit(&answer(&session), None).unwrap();
+        assert!(session.submit(&answer(&session), None).is_err());
+        assert!(session.replay().is_err());
+        assert_eq!(session.phase(), Phase::Answered);
+    }
+
+    #[test]
+    fn sessions_at_once_keep_each_others_answers() {
+        let mut first = session("ita", false);
+        let mut second = session("ita", false);
+
+        for session in [&mut first, &mut second] {
+            block_on(session.load_round()).unwrap();
+            while session.phase() != Phase::Finished {
+                session.submit(&answer(session), None).unwrap();
+                session.advance().unwrap();
+            }
+        }
+
+        let progress = WordProgress::load("ita").unwrap();
+        let answered: u32 = progress.forms.values().map(|score| score.total).sum();
+        assert_eq!(answered, 4);
+    }
+
+    #[test]
+    fn keeps_answers_from_a_round_left_unfinished() {
+        let mut session = scheduled("por", false, Scheduler::Sm2);
+
+        block_on(session.load_round()).unwrap();
+        let (sentence, prompt) = session.current().unwrap().clone();
+        session.submit(&prompt.word, None).unwrap();
+        drop(session);
+
+        let deck = Deck::load("por", "eng").unwrap();
+        let card = deck.card(sentence.id, prompt.word.trim()).unwrap();
+        assert_eq!(card.reps, 1);
+        assert!(KnownWords::load("por").unwrap().knows(&prompt.word));
+
+        let progress = WordProgress::load("por").unwrap();
+        let answered: u32 = progress.forms.values().map(|score| score.total).sum();
+        assert_eq!(answered, 1);
+    }
+
+    #[test]
+    fn reviews_misses() {
+        let mut session = session("spa", false);
+
+        block_on(session.load_round()).unwrap();
+        let (missed, prompt) = session.current().unwrap().clone();
+        session.submit("pájaro", None).unwrap();
+        session.advance().unwrap();
+        session.submit(&answer(&session), None).unwrap();
+        session.advance().unwrap();
+
+        let mut review = self::session("spa", true);
+        assert_eq!(block_on(review.load_round()), Ok(1));
+
+        let (sentence, blank) = review.current().unwrap();
+        assert_eq!(sentence.id, missed.id);
+        assert_eq!(blank.word, prompt.word);
+
+        review.submit(&answer(&review), None).unwrap();
+        assert!(missed::load("spa", "eng").unwrap().is_empty());
+    }
+}
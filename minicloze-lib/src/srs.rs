@@ -0,0 +1,348 @@
+// spaced repetition of the sentences answered before. every sentence and the word blanked in
+// it is a card, scheduled by SM-2 (https://super-memory.com/english/ol/sm2.htm) or FSRS
+// (https://github.com/open-spaced-repetition/fsrs4anki/wiki/The-Algorithm, version 4.5).
+// each round starts with the cards due, and is topped up with new sentences. cards failed
+// LEECH_LAPSES times are flagged as leeches, which usually need a mnemonic rather than more
+// reviews. every pair of languages has its own deck, srs/<native>-<language>.json in the data
+// dir, like the cache the sentences come from. FSRS's parameters can be fitted to a language's
+// answer history, see optimize.rs, and are kept in srs/fsrs-<language>.json
+
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::grading::Grade;
+use crate::paths::data_file;
+use crate::sentence::convert_error;
+
+// wrong answers before a card is a leech, as in anki
+pub const LEECH_LAPSES: u32 = 8;
+
+const DAY: f64 = 86_400.0;
+
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheduler {
+    // rounds are all new sentences and nothing is scheduled
+    Off,
+    Sm2,
+    #[default]
+    Fsrs,
+}
+
+// how well a card was remembered, FSRS's four buttons
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rating {
+    Again = 1,
+    Hard = 2,
+    Good = 3,
+    Easy = 4,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Card {
+    // tatoeba id of the sentence
+    pub sentence: i32,
+    // the blanked word
+    pub word: String,
+    // seconds since the unix epoch
+    pub due: u64,
+    pub last_review: Option<u64>,
+    // days until the card's due, from its last review
+    pub interval: f64,
+    // successful reviews in a row
+    pub reps: u32,
+    // times it was answered wrongly, whether it had been learnt yet or not, so a card that's
+    // never been right can still become a leech
+    pub lapses: u32,
+    // SM-2's ease factor
+    pub ease: f64,
+    // FSRS's memory state: days for recall to fall to 90%, and 1 to 10
+    pub stability: f64,
+    pub difficulty: f64,
+    pub leech: bool,
+}
+
+// FSRS's parameters, FSRS-4.5's defaults unless they've been fitted
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct Fsrs {
+    pub weights: [f64; 17],
+    // the chance of recalling a card when it's due
+    pub retention: f64,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct Deck {
+    pub cards: Vec<Card>,
+}
+
+impl From<Grade> for Rating {
+    fn from(grade: Grade) -> Rating {
+        match grade {
+            Grade::Correct => Rating::Good,
+            Grade::Close => Rating::Hard,
+            Grade::Wrong => Rating::Again,
+        }
+    }
+}
+
+impl Card {
+    pub fn new(sentence: i32, word: &str, now: u64) -> Card {
+        Card {
+            sentence,
+            word: word.to_string(),
+            due: now,
+            last_review: None,
+            interval: 0.0,
+            reps: 0,
+            lapses: 0,
+            ease: 2.5,
+            stability: 0.0,
+            difficulty: 0.0,
+            leech: false,
+        }
+    }
+
+    pub fn is_due(&self, now: u64) -> bool {
+        self.due <= now
+    }
+
+    // reschedules the card after a review at now
+    pub fn review(&mut self, rating: Rating, scheduler: Scheduler, fsrs: &Fsrs, now: u64) {
+        let elapsed = self
+            .last_review
+            .map_or(0.0, |last| now.saturating_sub(last) as f64 / DAY);
+
+        match scheduler {
+            Scheduler::Off => return,
+            Scheduler::Sm2 => self.sm2(rating),
+            Scheduler::Fsrs => self.fsrs(rating, elapsed, fsrs),
+        }
+
+        if rating == Rating::Again {
+            self.lapses += 1;
+            self.reps = 0;
+        } else {
+            self.reps += 1;
+        }
+
+        self.leech = self.lapses >= LEECH_LAPSES;
+        self.last_review = Some(now);
+        self.due = now + (self.interval * DAY).round() as u64;
+    }
+
+    fn sm2(&mut self, rating: Rating) {
+        // SM-2's 0 to 5 scale
+        let quality = match rating {
+            Rating::Again => 1.0,
+            Rating::Hard => 3.0,
+            Rating::Good => 4.0,
+            Rating::Easy => 5.0,
+        };
+
+        self.interval = match (rating, self.reps) {
+            (Rating::Again, _) => 1.0,
+            (_, 0) => 1.0,
+            (_, 1) => 6.0,
+            _ => (self.interval * self.ease).round(),
+        };
+
+        self.ease = (self.ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02)).max(1.3);
+    }
+
+    pub(crate) fn fsrs(&mut self, rating: Rating, elapsed: f64, fsrs: &Fsrs) {
+        let w = &fsrs.weights;
+        let grade = rating as u8 as f64;
+
+        if self.stability == 0.0 {
+            self.stability = w[rating as usize - 1];
+            self.difficulty = fsrs.initial_difficulty(grade);
+        } else {
+            let retrievability = Fsrs::retrievability(elapsed, self.stability);
+
+            self.stability = if rating == Rating::Again {
+                w[11]
+                    * self.difficulty.powf(-w[12])
+                    * ((self.stability + 1.0).powf(w[13]) - 1.0)
+                    * (w[14] * (1.0 - retrievability)).exp()
+            } else {
+                let hard = if rating == Rating::Hard { w[15] } else { 1.0 };
+                let easy = if rating == Rating::Easy { w[16] } else { 1.0 };
+
+                self.stability
+                    * (w[8].exp()
+                        * (11.0 - self.difficulty)
+                        * self.stability.powf(-w[9])
+                        * ((w[10] * (1.0 - retrievability)).exp() - 1.0)
+                        * hard
+                        * easy
+                        + 1.0)
+            };
+
+            // mean reversion towards the difficulty of a card first rated good
+            let difficulty = self.difficulty - w[6] * (grade - 3.0);
+            self.difficulty =
+                (w[7] * fsrs.initial_difficulty(3.0) + (1.0 - w[7]) * difficulty).clamp(1.0, 10.0);
+        }
+
+        self.interval = if rating == Rating::Again {
+            // forgotten cards come back the next day
+            1.0
+        } else {
+            fsrs.interval(self.stability).max(1.0)
+        };
+    }
+}
+
+impl Default for Fsrs {
+    fn default() -> Fsrs {
+        Fsrs {
+            weights: [
+                0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367,
+                1.0461, 2.1072, 0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+            ],
+            retention: 0.9,
+        }
+    }
+}
+
+impl Fsrs {
+    const DECAY: f64 = -0.5;
+    const FACTOR: f64 = 19.0 / 81.0;
+
+    // the language's fitted parameters, or the defaults
+    pub fn load(language: &str) -> Result<Fsrs, String> {
+        let path = data_file("srs", &format!("fsrs-{language}.json"))?;
+
+        if !path.exists() {
+            return Ok(Fsrs::default());
+        }
+
+        let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(convert_error)
+    }
+
+    pub fn save(&self, language: &str) -> Result<(), String> {
+        let path = data_file("srs", &format!("fsrs-{language}.json"))?;
+        let json = serde_json::to_string_pretty(self).map_err(convert_error)?;
+
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    // the chance of recalling a card elapsed days after its last review
+    pub fn retrievability(elapsed: f64, stability: f64) -> f64 {
+        (1.0 + Fsrs::FACTOR * elapsed / stability).powf(Fsrs::DECAY)
+    }
+
+    // days until recall falls to the target retention
+    pub fn interval(&self, stability: f64) -> f64 {
+        (stability / Fsrs::FACTOR * (self.retention.powf(1.0 / Fsrs::DECAY) - 1.0)).round()
+    }
+
+    fn initial_difficulty(&self, grade: f64) -> f64 {
+        (self.weights[4] - (grade - 3.0) * self.weights[5]).clamp(1.0, 10.0)
+    }
+}
+
+fn file_name(language: &str, native: &str) -> String {
+    format!("{native}-{language}.json")
+}
+
+impl Deck {
+    pub fn load(language: &str, native: &str) -> Result<Deck, String> {
+        let path = data_file("srs", &file_name(language, native))?;
+
+        if !path.exists() {
+            return Ok(Deck::default());
+        }
+
+        let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&json).map_err(convert_error)
+    }
+
+    pub fn save(&self, language: &str, native: &str) -> Result<(), String> {
+        let path = data_file("srs", &file_name(language, native))?;
+        let json = serde_json::to_string(self).map_err(convert_error)?;
+
+        fs::write(path, json).map_err(|e| e.to_string())
+    }
+
+    pub fn card(&self, sentence: i32, word: &str) -> Option<&Card> {
+        self.cards
+            .iter()
+            .find(|card| card.sentence == sentence && card.word == word)
+    }
+
+    // reviews a card, adding it if it's new
+    pub fn review(
+        &mut self,
+        sentence: i32,
+        word: &str,
+        rating: Rating,
+        scheduler: Scheduler,
+        fsrs: &Fsrs,
+        now: u64,
+    ) -> &Card {
+        let index = match self
+            .cards
+            .iter()
+            .position(|card| card.sentence == sentence && card.word == word)
+        {
+            Some(index) => index,
+            None => {
+                self.cards.push(Card::new(sentence, word, now));
+                self.cards.len() - 1
+            }
+        };
+
+        let card = &mut self.cards[index];
+        card.review(rating, scheduler, fsrs, now);
+        card
+    }
+
+    // the cards due at now, most overdue first
+    pub fn due(&self, now: u64) -> Vec<&Card> {
+        let mut due: Vec<&Card> = self.cards.iter().filter(|card| card.is_due(now)).collect();
+        due.sort_by_key(|card| card.due);
+        due
+    }
+
+    pub fn leeches(&self) -> Vec<&Card> {
+        self.cards.iter().filter(|card| card.leech).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_card_never_answered_right_becomes_a_leech() {
+        for scheduler in [Scheduler::Sm2, Scheduler::Fsrs] {
+            let mut card = Card::new(1, "chat", 0);
+            let mut now = 0;
+
+            for _ in 0..LEECH_LAPSES {
+                assert!(!card.leech);
+                card.review(Rating::Again, scheduler, &Fsrs::default(), now);
+                now = card.due + 1;
+            }
+
+            assert_eq!(card.lapses, LEECH_LAPSES);
+            assert!(card.leech);
+        }
+    }
+
+    #[test]
+    fn nothing_changes_with_the_scheduler_off() {
+        let mut card = Card::new(1, "chat", 0);
+
+        for _ in 0..LEECH_LAPSES {
+            card.review(Rating::Again, Scheduler::Off, &Fsrs::default(), 0);
+        }
+
+        assert_eq!(card.lapses, 0);
+        assert!(!card.leech);
+        assert_eq!(card.last_review, None);
+    }
+}
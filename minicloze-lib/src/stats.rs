@@ -0,0 +1,326 @@
+// figures from the answer history (see history.rs) for `minicloze stats`. days are counted in
+// UTC, from the unix epoch, and weeks start on monday
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::grading::Grade;
+use crate::history::Record;
+
+// right every time on this many different days in a row, and a word's mastered
+pub const MASTERED_STREAK: usize = 3;
+
+const DAY: u64 = 86_400;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WordCounts {
+    // answered at least once
+    pub seen: usize,
+    // right the last time
+    pub learned: usize,
+    // right every time it was answered on its last MASTERED_STREAK days
+    pub mastered: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Streaks {
+    // days in a row up to today, or yesterday if nothing's been answered yet today
+    pub current: usize,
+    pub longest: usize,
+}
+
+// the day a unix timestamp falls on
+pub fn day(timestamp: u64) -> i64 {
+    (timestamp / DAY) as i64
+}
+
+// the day of a date written as YYYY-MM-DD
+pub fn parse_day(date: &str) -> Result<i64, String> {
+    let invalid = || format!("\"{date}\" isn't a date like 2024-01-31");
+
+    let parts: Vec<i64> = date
+        .split('-')
+        .map(|part| part.parse().map_err(|_| invalid()))
+        .collect::<Result<_, _>>()?;
+    let [year, month, day] = parts[..] else {
+        return Err(invalid());
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = [
+        31,
+        if leap { 29 } else { 28 },
+        31,
+        30,
+        31,
+        30,
+        31,
+        31,
+        30,
+        31,
+        30,
+        31,
+    ];
+    if !(1..=12).contains(&month) || !(1..=days_in_month[month as usize - 1]).contains(&day) {
+        return Err(invalid());
+    }
+
+    // days since 0000-03-01, with march first so the leap day comes last, then since the epoch.
+    // see https://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    Ok(era * 146_097 + day_of_era - 719_468)
+}
+
+// 0 for monday to 6 for sunday. 1970-01-01 was a thursday
+pub fn weekday(day: i64) -> usize {
+    (day + 3).rem_euclid(7) as usize
+}
+
+// answers per day
+pub fn daily_activity(records: &[Record]) -> BTreeMap<i64, usize> {
+    let mut days = BTreeMap::new();
+
+    for record in records {
+        *days.entry(day(record.timestamp)).or_default() += 1;
+    }
+
+    days
+}
+
+pub fn streaks(activity: &BTreeMap<i64, usize>, today: i64) -> Streaks {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous = None;
+
+    for &day in activity.keys() {
+        run = if previous == Some(day - 1) {
+            run + 1
+        } else {
+            1
+        };
+        longest = longest.max(run);
+        previous = Some(day);
+    }
+
+    // the run so far only counts if it reaches today or yesterday
+    let current = match previous {
+        Some(last) if last >= today - 1 => run,
+        _ => 0,
+    };
+
+    Streaks { current, longest }
+}
+
+// the share answered right in each of the last weeks, oldest first, None for weeks without
+// answers. the last week is the one today's in
+pub fn weekly_accuracy(records: &[Record], today: i64, weeks: usize) -> Vec<Option<f64>> {
+    let this_week = today - weekday(today) as i64;
+    let mut tallies = vec![(0, 0); weeks];
+
+    for record in records {
+        let day = day(record.timestamp);
+        let ago = (this_week - (day - weekday(day) as i64)) / 7;
+
+        if (0..weeks as i64).contains(&ago) {
+            let tally = &mut tallies[weeks - 1 - ago as usize];
+            tally.0 += usize::from(record.grade == Grade::Correct);
+            tally.1 += 1;
+        }
+    }
+
+    tallies
+        .into_iter()
+        .map(|(correct, total)| (total > 0).then(|| correct as f64 / total as f64))
+        .collect()
+}
+
+// milliseconds, over the answers which were timed
+pub fn average_response(records: &[Record]) -> Option<f64> {
+    let times: Vec<u64> = records.iter().filter_map(|r| r.response_ms).collect();
+
+    (!times.is_empty()).then(|| times.iter().sum::<u64>() as f64 / times.len() as f64)
+}
+
+// the words of the language being learnt, ignoring case. answers in inverse mode are english
+pub fn word_counts(records: &[Record]) -> WordCounts {
+    let mut words: HashMap<String, Vec<&Record>> = HashMap::new();
+
+    for record in records.iter().filter(|record| !record.inverse) {
+        words
+            .entry(record.word.to_lowercase())
+            .or_default()
+            .push(record);
+    }
+
+    let mut counts = WordCounts {
+        seen: words.len(),
+        ..WordCounts::default()
+    };
+
+    for answers in words.values_mut() {
+        answers.sort_by_key(|record| record.timestamp);
+
+        let right: Vec<&&Record> = answers
+            .iter()
+            .rev()
+            .take_while(|record| record.grade == Grade::Correct)
+            .collect();
+
+        if !right.is_empty() {
+            counts.learned += 1;
+        }
+
+        let mut days: Vec<i64> = right.iter().map(|record| day(record.timestamp)).collect();
+        days.dedup();
+        if days.len() >= MASTERED_STREAK {
+            counts.mastered += 1;
+        }
+    }
+
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(date: &str) -> i64 {
+        parse_day(date).unwrap()
+    }
+
+    // an answer at noon on the date
+    fn answer(date: &str, word: &str, grade: Grade) -> Record {
+        Record {
+            sentence: 1,
+            language: String::from("fra"),
+            native: String::from("eng"),
+            inverse: false,
+            word: word.to_string(),
+            guess: String::new(),
+            grade,
+            response_ms: None,
+            timestamp: on(date) as u64 * DAY + DAY / 2,
+        }
+    }
+
+    #[test]
+    fn parses_days() {
+        assert_eq!(on("1970-01-01"), 0);
+        assert_eq!(on("1969-12-31"), -1);
+        assert_eq!(on("2024-01-01"), on("2023-12-31") + 1);
+        assert_eq!(on("2024-02-01"), on("2024-01-31") + 1);
+        assert_eq!(on("2024-03-01"), on("2024-02-29") + 1);
+        assert_eq!(on("2000-03-01"), on("2000-02-29") + 1);
+        assert_eq!(
+            day(on("2024-05-17") as u64 * DAY + DAY - 1),
+            on("2024-05-17")
+        );
+        assert_eq!(weekday(on("2024-01-01")), 0);
+
+        for date in [
+            "2023-02-29",
+            "1900-02-29",
+            "2024-13-01",
+            "2024-04-31",
+            "2024-01",
+            "today",
+        ] {
+            assert!(parse_day(date).is_err(), "{date}");
+        }
+    }
+
+    #[test]
+    fn counts_streaks() {
+        assert_eq!(
+            streaks(&BTreeMap::new(), on("2024-01-02")),
+            Streaks::default()
+        );
+
+        // three days over new year, then a day off, then two more
+        let days = [
+            "2023-12-30",
+            "2023-12-31",
+            "2024-01-01",
+            "2024-01-03",
+            "2024-01-04",
+        ];
+        let records: Vec<Record> = days
+            .iter()
+            .map(|date| answer(date, "chat", Grade::Correct))
+            .collect();
+        let activity = daily_activity(&records);
+
+        let today = streaks(&activity, on("2024-01-04"));
+        assert_eq!(
+            today,
+            Streaks {
+                current: 2,
+                longest: 3
+            }
+        );
+        // nothing yet today doesn't break it
+        assert_eq!(streaks(&activity, on("2024-01-05")).current, 2);
+        // a day missed does
+        assert_eq!(streaks(&activity, on("2024-01-06")).current, 0);
+    }
+
+    #[test]
+    fn tallies_weeks() {
+        let today = on("2024-01-03");
+        assert_eq!(weekly_accuracy(&[], today, 3), [None, None, None]);
+
+        let records = [
+            // the monday of this week
+            answer("2024-01-01", "chat", Grade::Correct),
+            // last week, which began in december
+            answer("2023-12-31", "chat", Grade::Wrong),
+            answer("2023-12-25", "chien", Grade::Correct),
+            answer("2023-12-25", "chien", Grade::Close),
+            answer("2023-12-25", "chien", Grade::Correct),
+            // three weeks ago, too long ago
+            answer("2023-12-17", "chat", Grade::Correct),
+        ];
+
+        assert_eq!(
+            weekly_accuracy(&records, today, 3),
+            [None, Some(0.5), Some(1.0)]
+        );
+    }
+
+    #[test]
+    fn counts_words() {
+        assert_eq!(word_counts(&[]), WordCounts::default());
+
+        let mut inverse = answer("2024-01-01", "cat", Grade::Correct);
+        inverse.inverse = true;
+
+        let records = [
+            // right on three days, whatever the case
+            answer("2024-01-01", "Chat", Grade::Correct),
+            answer("2024-01-02", "chat", Grade::Correct),
+            answer("2024-01-02", "chat", Grade::Correct),
+            answer("2024-01-03", "chat", Grade::Correct),
+            // right since the last miss, but only on two days
+            answer("2023-12-31", "chien", Grade::Wrong),
+            answer("2024-01-01", "chien", Grade::Correct),
+            answer("2024-01-02", "chien", Grade::Correct),
+            // wrong the last time
+            answer("2024-01-01", "oiseau", Grade::Correct),
+            answer("2024-01-02", "oiseau", Grade::Close),
+            inverse,
+        ];
+
+        assert_eq!(
+            word_counts(&records),
+            WordCounts {
+                seen: 3,
+                learned: 2,
+                mastered: 1,
+            }
+        );
+    }
+}
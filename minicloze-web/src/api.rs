@@ -0,0 +1,447 @@
+// the JSON API, and the page which plays through it. each game is a GameSession kept in memory
+// under a random id, until it's deleted or left alone for too long. the word to guess is never
+// sent before it's been answered, so the page can't give it away
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use minicloze_lib::{
+    config::Config,
+    grading::Feedback,
+    i18n::render,
+    langs::Language,
+    missed::MissFilter,
+    morph::Lexicon,
+    resolve::resolve_language,
+    sentence::Strategy,
+    session::{GameSession, Phase, PromptView, SessionOptions, Source, Summary},
+};
+
+const INDEX: &str = include_str!("../static/index.html");
+
+// games nobody has touched for this long are dropped when a new one starts
+const IDLE: Duration = Duration::from_secs(60 * 60);
+// how many games can be kept at once, so a server left running can't fill up its memory
+const MAX_GAMES: usize = 1000;
+
+// a game and the lexicon it's graded with. a game is locked while its round loads, which can
+// take a while, so every game has its own lock
+struct Game {
+    session: GameSession,
+    lexicon: Option<Lexicon>,
+}
+
+// a game and when it was last asked for
+struct Slot {
+    game: Arc<tokio::sync::Mutex<Game>>,
+    used: Instant,
+}
+
+type Games = Arc<Mutex<HashMap<String, Slot>>>;
+
+pub struct Error {
+    status: StatusCode,
+    message: String,
+}
+
+type ApiResult<T> = Result<Json<T>, Error>;
+
+// POST /api/sessions. only the language is needed, the rest comes from the config file
+#[derive(Deserialize)]
+struct NewSession {
+    language: String,
+    native: Option<String>,
+    inverse: Option<bool>,
+    round_size: Option<usize>,
+    strategy: Option<Strategy>,
+    source: Option<Source>,
+    #[serde(default)]
+    review: bool,
+}
+
+#[derive(Deserialize)]
+struct Guess {
+    guess: String,
+}
+
+#[derive(Serialize)]
+struct LanguageView {
+    code: &'static str,
+    name: &'static str,
+    endonym: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct SessionView {
+    id: String,
+    language: String,
+    native: String,
+    inverse: bool,
+    phase: Phase,
+    prompt: Option<PromptView>,
+    summary: Summary,
+}
+
+#[derive(Serialize)]
+struct AnswerView {
+    word: String,
+    guess: String,
+    feedback: Feedback,
+    // what went wrong, in words
+    description: Option<String>,
+    // whether the word keeps being forgotten, see srs.rs
+    leech: bool,
+}
+
+pub fn router() -> Router {
+    let games = Games::default();
+
+    Router::new()
+        .route("/", get(|| async { Html(INDEX) }))
+        .route("/api/languages", get(languages))
+        .route("/api/sessions", post(create))
+        .route("/api/sessions/:id", get(show).delete(remove))
+        .route("/api/sessions/:id/answer", post(answer))
+        .route("/api/sessions/:id/next", post(next))
+        .route("/api/sessions/:id/summary", get(summary))
+        .route("/api/sessions/:id/replay", post(replay))
+        .with_state(games)
+}
+
+async fn languages() -> Json<Vec<LanguageView>> {
+    Json(
+        Language::all()
+            .iter()
+            .map(|language| LanguageView {
+                code: language.code,
+                name: language.name,
+                endonym: language.endonym,
+            })
+            .collect(),
+    )
+}
+
+async fn create(State(games): State<Games>, Json(new): Json<NewSession>) -> ApiResult<SessionView> {
+    let language = resolve_language(&new.language).map_err(Error::bad_request)?;
+    let settings = Config::load()
+        .map_err(Error::internal)?
+        .settings(language.code);
+    let native = resolve_language(new.native.as_deref().unwrap_or(&settings.native))
+        .map_err(Error::bad_request)?;
+
+    let options = SessionOptions {
+        language: language.code.to_string(),
+        native: native.code.to_string(),
+        inverse: new.inverse.unwrap_or(settings.inverse),
+        round_size: new.round_size.unwrap_or(settings.round_size),
+        strategy: new.strategy.unwrap_or(settings.strategy),
+        source: new.source.unwrap_or(Source::Tatoeba),
+        review: new.review,
+        filter: MissFilter::default(),
+        grading: settings.grading,
+        scheduler: settings.scheduler,
+    };
+
+    let mut game = Game {
+        session: GameSession::new(options, None),
+        // grading without one only loses the inflection feedback
+        lexicon: Lexicon::load(language.code).unwrap_or(None),
+    };
+    load(&mut game.session).await?;
+
+    let id = format!("{:016x}", rand::random::<u64>());
+    let view = view(&id, &game.session);
+
+    let mut games = games.lock().unwrap();
+    games.retain(|_, slot| slot.used.elapsed() < IDLE);
+
+    if games.len() >= MAX_GAMES {
+        return Err(Error {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            message: String::from("Too many games are being played, try again later"),
+        });
+    }
+
+    games.insert(
+        id,
+        Slot {
+            game: Arc::new(tokio::sync::Mutex::new(game)),
+            used: Instant::now(),
+        },
+    );
+
+    Ok(Json(view))
+}
+
+async fn show(State(games): State<Games>, Path(id): Path<String>) -> ApiResult<SessionView> {
+    let game = find(&games, &id)?;
+    let game = game.lock().await;
+
+    Ok(Json(view(&id, &game.session)))
+}
+
+async fn remove(State(games): State<Games>, Path(id): Path<String>) -> Result<StatusCode, Error> {
+    match games.lock().unwrap().remove(&id) {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Err(Error::not_found(&id)),
+    }
+}
+
+async fn answer(
+    State(games): State<Games>,
+    Path(id): Path<String>,
+    Json(guess): Json<Guess>,
+) -> ApiResult<AnswerView> {
+    let game = find(&games, &id)?;
+    let mut game = game.lock().await;
+    let Game { session, lexicon } = &mut *game;
+
+    let feedback = session
+        .submit(&guess.guess, lexicon.as_ref())
+        .map_err(Error::conflict)?;
+    let word = session
+        .current()
+        .map(|(_, prompt)| prompt.word.trim().to_string())
+        .unwrap_or_default();
+
+    let description = feedback.describe().as_ref().map(render);
+
+    Ok(Json(AnswerView {
+        word,
+        guess: guess.guess,
+        feedback,
+        description,
+        leech: session.leech(),
+    }))
+}
+
+async fn next(State(games): State<Games>, Path(id): Path<String>) -> ApiResult<SessionView> {
+    let game = find(&games, &id)?;
+    let mut game = game.lock().await;
+
+    game.session.advance().map_err(Error::conflict)?;
+
+    Ok(Json(view(&id, &game.session)))
+}
+
+async fn summary(State(games): State<Games>, Path(id): Path<String>) -> ApiResult<Summary> {
+    let game = find(&games, &id)?;
+    let game = game.lock().await;
+
+    Ok(Json(game.session.summary()))
+}
+
+async fn replay(State(games): State<Games>, Path(id): Path<String>) -> ApiResult<SessionView> {
+    let game = find(&games, &id)?;
+    let mut game = game.lock().await;
+
+    game.session.replay().map_err(Error::conflict)?;
+    load(&mut game.session).await?;
+
+    Ok(Json(view(&id, &game.session)))
+}
+
+fn find(games: &Games, id: &str) -> Result<Arc<tokio::sync::Mutex<Game>>, Error> {
+    let mut games = games.lock().unwrap();
+    let slot = games.get_mut(id).ok_or_else(|| Error::not_found(id))?;
+
+    slot.used = Instant::now();
+    Ok(slot.game.clone())
+}
+
+// a round with no sentences is an error here, the session stays loading so it can be retried
+async fn load(session: &mut GameSession) -> Result<usize, Error> {
+    let review = session.options().review;
+
+    match session.load_round().await {
+        Ok(0) if review => Err(Error::unprocessable(
+            "Nothing to review yet: you haven't missed anything",
+        )),
+        Ok(0) => Err(Error::unprocessable("No sentences found for this language")),
+        Ok(len) => Ok(len),
+        Err(e) => Err(Error {
+            status: StatusCode::BAD_GATEWAY,
+            message: e,
+        }),
+    }
+}
+
+fn view(id: &str, session: &GameSession) -> SessionView {
+    let options = session.options();
+
+    SessionView {
+        id: id.to_string(),
+        language: options.language.clone(),
+        native: options.native.clone(),
+        inverse: options.inverse,
+        phase: session.phase(),
+        prompt: session.view(),
+        summary: session.summary(),
+    }
+}
+
+impl Error {
+    fn bad_request(message: String) -> Error {
+        Error {
+            status: StatusCode::BAD_REQUEST,
+            message,
+        }
+    }
+
+    fn not_found(id: &str) -> Error {
+        Error {
+            status: StatusCode::NOT_FOUND,
+            message: format!("No game with the id {id}"),
+        }
+    }
+
+    // asking a session for something it can't do in its phase, e.g. answering twice
+    fn conflict(message: String) -> Error {
+        Error {
+            status: StatusCode::CONFLICT,
+            message,
+        }
+    }
+
+    fn unprocessable(message: &str) -> Error {
+        Error {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            message: message.to_string(),
+        }
+    }
+
+    fn internal(message: String) -> Error {
+        Error {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorView {
+    error: String,
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let body = Json(ErrorView {
+            error: self.message,
+        });
+        (self.status, body).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{header::CONTENT_TYPE, Client, Method};
+    use serde_json::{json, Value};
+
+    use minicloze_lib::{
+        cache,
+        sentence::{Sentence, Translation},
+    };
+
+    use super::*;
+
+    // a server on a free port, with its config and data in a directory of their own and one
+    // sentence cached to play offline
+    async fn serve() -> String {
+        let dir = std::env::temp_dir().join(format!("minicloze-web-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::env::set_var("XDG_CONFIG_HOME", &dir);
+        std::env::set_var("XDG_DATA_HOME", &dir);
+
+        let sentence = Sentence {
+            id: 1,
+            text: String::from("cat"),
+            translations: vec![Translation {
+                id: 2,
+                text: String::from("chat"),
+                transcriptions: Vec::new(),
+            }],
+        };
+        cache::store("fra", "eng", &[sentence]).unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async { axum::serve(listener, router()).await });
+
+        format!("http://{address}")
+    }
+
+    async fn request(method: Method, url: &str, body: Option<Value>) -> (StatusCode, Value) {
+        let mut request = Client::new().request(method, url);
+        if let Some(body) = body {
+            request = request
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.to_string());
+        }
+
+        let response = request.send().await.unwrap();
+        let status = StatusCode::from_u16(response.status().as_u16()).unwrap();
+        let text = response.text().await.unwrap();
+
+        (status, serde_json::from_str(&text).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn plays_a_game() {
+        let server = serve().await;
+
+        let new = json!({"language": "fr", "native": "eng", "source": "cache", "round_size": 1});
+        let (status, session) =
+            request(Method::POST, &format!("{server}/api/sessions"), Some(new)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(session["phase"], "prompting");
+        assert_eq!(session["prompt"]["letters"], 4);
+
+        let game = format!("{server}/api/sessions/{}", session["id"].as_str().unwrap());
+
+        let guess = json!({"guess": "chat"});
+        let (status, answer) =
+            request(Method::POST, &format!("{game}/answer"), Some(guess.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(answer["word"], "chat");
+        assert_eq!(answer["feedback"]["grade"], "Correct");
+
+        let (status, _) = request(Method::POST, &format!("{game}/answer"), Some(guess)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, session) = request(Method::POST, &format!("{game}/next"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(session["phase"], "finished");
+
+        let (status, summary) = request(Method::GET, &format!("{game}/summary"), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            (summary["correct"].clone(), summary["total"].clone()),
+            (json!(1), json!(1))
+        );
+
+        let (status, _) = request(Method::DELETE, &game, None).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, error) = request(Method::GET, &game, None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(error["error"].is_string());
+
+        let (status, _) = request(
+            Method::GET,
+            &format!("{server}/api/sessions/nonexistent"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,34 @@
+// the web frontend: a page to play in the browser, and the JSON API it plays through, which
+// other clients can use too. see api.rs for the routes
+
+mod api;
+
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(version, about = "Play minicloze in the browser")]
+struct Args {
+    /// The address to listen on. Use 0.0.0.0:7878 to let the rest of the network connect
+    #[arg(long, default_value = "127.0.0.1:7878")]
+    address: String,
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Args::parse();
+
+    let listener = match tokio::net::TcpListener::bind(&args.address).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Couldn't listen on {}: {e}", args.address);
+            std::process::exit(1);
+        }
+    };
+
+    println!("Playing minicloze on http://{}", args.address);
+
+    if let Err(e) = axum::serve(listener, api::router()).await {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}